@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use egui_node_graph::NodeId;
+use serde::{Deserialize, Serialize};
+
+use crate::csg_node_graph::ValueType;
+
+/// How a keyframe's value changes towards the next keyframe.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Interpolation {
+    Linear,
+    /// Cubic Bezier with automatic handles, smooth through the neighbouring keyframes.
+    Bezier,
+    /// Hold the value until the next keyframe.
+    Step,
+}
+
+impl Interpolation {
+    pub(crate) const ALL: [Interpolation; 3] = [
+        Interpolation::Linear,
+        Interpolation::Bezier,
+        Interpolation::Step,
+    ];
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Interpolation::Linear => "Linear",
+            Interpolation::Bezier => "Bezier",
+            Interpolation::Step => "Step",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Keyframe {
+    /// Time in seconds.
+    pub(crate) time: f32,
+    /// Either a `Scalar` or a `Vec3`, like the input it animates.
+    pub(crate) value: ValueType,
+    pub(crate) interpolation: Interpolation,
+}
+
+/// Keyframes of a single input, sorted by time.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Track {
+    keyframes: Vec<Keyframe>,
+}
+
+impl Track {
+    pub(crate) fn new(mut keyframes: Vec<Keyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { keyframes }
+    }
+
+    pub(crate) fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Two keyframes closer than this are considered at the same time.
+    const TIME_EPSILON: f32 = 1e-3;
+
+    pub(crate) fn has_keyframe_at(&self, time: f32) -> bool {
+        self.keyframes
+            .iter()
+            .any(|keyframe| (keyframe.time - time).abs() < Self::TIME_EPSILON)
+    }
+
+    /// Insert a keyframe, or replace the value of the keyframe at the same time.
+    pub(crate) fn set_keyframe(&mut self, time: f32, value: ValueType) {
+        if let Some(keyframe) = self
+            .keyframes
+            .iter_mut()
+            .find(|keyframe| (keyframe.time - time).abs() < Self::TIME_EPSILON)
+        {
+            keyframe.value = value;
+            return;
+        }
+
+        let index = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time < time);
+        // New keyframes continue the interpolation of the segment they split.
+        let interpolation = index
+            .checked_sub(1)
+            .map_or(Interpolation::Linear, |i| self.keyframes[i].interpolation);
+        self.keyframes.insert(
+            index,
+            Keyframe {
+                time,
+                value,
+                interpolation,
+            },
+        );
+    }
+
+    pub(crate) fn remove_keyframe(&mut self, index: usize) {
+        self.keyframes.remove(index);
+    }
+
+    /// Move keyframes to new times, given by their indices before any of them is moved.
+    pub(crate) fn set_keyframe_times(&mut self, times: &[(usize, f32)]) {
+        for &(index, time) in times {
+            self.keyframes[index].time = time;
+        }
+        self.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    pub(crate) fn set_interpolation(&mut self, index: usize, interpolation: Interpolation) {
+        self.keyframes[index].interpolation = interpolation;
+    }
+
+    /// Value of the curve at `time`, held constant before the first and after the last keyframe.
+    pub(crate) fn sample(&self, time: f32) -> Option<ValueType> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        if time <= first.time {
+            return Some(first.value.clone());
+        }
+        if time >= last.time {
+            return Some(last.value.clone());
+        }
+
+        let i = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time)
+            - 1;
+        let (k0, k1) = (&self.keyframes[i], &self.keyframes[i + 1]);
+        let duration = k1.time - k0.time;
+        let u = (time - k0.time) / duration;
+
+        let p0 = components(&k0.value);
+        let p1 = components(&k1.value);
+        let values = match k0.interpolation {
+            Interpolation::Step => p0,
+            Interpolation::Linear => (0..p0.len()).map(|c| p0[c] + (p1[c] - p0[c]) * u).collect(),
+            Interpolation::Bezier => {
+                // Cubic Hermite spline with Catmull-Rom tangents, which is a cubic Bezier with
+                // handles at a third of the tangents. Tangents are flat at the ends of the track.
+                let m0 = self.tangent(i);
+                let m1 = self.tangent(i + 1);
+                let (u2, u3) = (u * u, u * u * u);
+                let h00 = 2.0 * u3 - 3.0 * u2 + 1.0;
+                let h10 = u3 - 2.0 * u2 + u;
+                let h01 = -2.0 * u3 + 3.0 * u2;
+                let h11 = u3 - u2;
+                (0..p0.len())
+                    .map(|c| {
+                        h00 * p0[c] + h10 * m0[c] * duration + h01 * p1[c] + h11 * m1[c] * duration
+                    })
+                    .collect()
+            }
+        };
+        Some(from_components(&k0.value, &values))
+    }
+
+    /// Rate of change at keyframe `i`, per second.
+    fn tangent(&self, i: usize) -> Vec<f32> {
+        let dimension = components(&self.keyframes[i].value).len();
+        if i == 0 || i + 1 == self.keyframes.len() {
+            return vec![0.0; dimension];
+        }
+
+        let (previous, next) = (&self.keyframes[i - 1], &self.keyframes[i + 1]);
+        let (p_previous, p_next) = (components(&previous.value), components(&next.value));
+        let duration = next.time - previous.time;
+        (0..dimension)
+            .map(|c| (p_next[c] - p_previous[c]) / duration)
+            .collect()
+    }
+}
+
+fn components(value: &ValueType) -> Vec<f32> {
+    match value {
+        ValueType::Scalar(x) => vec![*x],
+        ValueType::Vec3(x) => x.to_vec(),
+        _ => unreachable!("only scalars and vectors are animated"),
+    }
+}
+
+fn from_components(like: &ValueType, values: &[f32]) -> ValueType {
+    match like {
+        ValueType::Scalar(_) => ValueType::Scalar(values[0]),
+        ValueType::Vec3(_) => ValueType::Vec3([values[0], values[1], values[2]]),
+        _ => unreachable!("only scalars and vectors are animated"),
+    }
+}
+
+/// Keyframe tracks of the constant inputs of a node graph, by node and input name.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Animation {
+    tracks: HashMap<(NodeId, String), Track>,
+}
+
+impl Animation {
+    pub(crate) fn track(&self, node_id: NodeId, param_name: &str) -> Option<&Track> {
+        self.tracks.get(&(node_id, param_name.to_string()))
+    }
+
+    pub(crate) fn track_mut(&mut self, node_id: NodeId, param_name: &str) -> &mut Track {
+        self.tracks
+            .entry((node_id, param_name.to_string()))
+            .or_default()
+    }
+
+    pub(crate) fn insert_track(&mut self, node_id: NodeId, param_name: &str, track: Track) {
+        self.tracks.insert((node_id, param_name.to_string()), track);
+    }
+
+    pub(crate) fn tracks(&self) -> impl Iterator<Item = (&(NodeId, String), &Track)> {
+        self.tracks.iter()
+    }
+
+    pub(crate) fn sample(&self, node_id: NodeId, param_name: &str, time: f32) -> Option<ValueType> {
+        self.track(node_id, param_name)?.sample(time)
+    }
+
+    pub(crate) fn remove_track(&mut self, node_id: NodeId, param_name: &str) {
+        self.tracks.remove(&(node_id, param_name.to_string()));
+    }
+
+    /// Remove the tracks of a deleted node.
+    pub(crate) fn remove_node(&mut self, node_id: NodeId) {
+        self.tracks
+            .retain(|(track_node_id, _), _| *track_node_id != node_id);
+    }
+
+    /// Remove tracks whose last keyframe was deleted.
+    pub(crate) fn remove_empty_tracks(&mut self) {
+        self.tracks.retain(|_, track| !track.keyframes.is_empty());
+    }
+}
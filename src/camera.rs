@@ -1,4 +1,5 @@
 use nalgebra::{convert, Affine3, Point3, Translation3, UnitQuaternion, Vector3};
+use serde::{Deserialize, Serialize};
 
 pub(crate) struct Camera {
     position: Point3<f32>,
@@ -18,6 +19,15 @@ pub(crate) enum OrbitCameraControllerEvent {
     Dolly(f32),
 }
 
+/// Placement of an orbit camera, as saved in scene files.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct OrbitCameraPose {
+    pub(crate) target: [f32; 3],
+    pub(crate) pitch: f32,
+    pub(crate) yaw: f32,
+    pub(crate) radius: f32,
+}
+
 pub(crate) struct OrbitCameraController {
     /// Target to look at.
     target: Point3<f32>,
@@ -49,6 +59,22 @@ impl OrbitCameraController {
         }
     }
 
+    pub(crate) fn pose(&self) -> OrbitCameraPose {
+        OrbitCameraPose {
+            target: self.target.into(),
+            pitch: self.pitch,
+            yaw: self.yaw,
+            radius: self.radius,
+        }
+    }
+
+    pub(crate) fn set_pose(&mut self, pose: &OrbitCameraPose) {
+        self.target = Point3::from(pose.target);
+        self.pitch = pose.pitch.clamp(-1.5, 1.5);
+        self.yaw = pose.yaw;
+        self.radius = pose.radius.max(0.1);
+    }
+
     fn rotation(&self) -> UnitQuaternion<f32> {
         UnitQuaternion::from_euler_angles(-self.pitch, -self.yaw, 0.0)
     }
@@ -0,0 +1,84 @@
+use eframe::egui;
+
+/// Animation clock, read by the `Time` node.
+pub(crate) struct Clock {
+    /// Current time in seconds.
+    time: f32,
+    playing: bool,
+    /// Length of the animation in seconds, playback loops back to the start after it.
+    duration: f32,
+    /// Whether to advance by exactly `1 / fps` per frame instead of by the real frame time,
+    /// so every frame is rendered regardless of how long it takes.
+    fixed_step: bool,
+    fps: f32,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            playing: false,
+            duration: 10.0,
+            fixed_step: false,
+            fps: 60.0,
+        }
+    }
+}
+
+impl Clock {
+    pub(crate) fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub(crate) fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Advance the clock by one frame, and keep repainting while playing.
+    pub(crate) fn update(&mut self, ctx: &egui::Context) {
+        if !self.playing {
+            return;
+        }
+
+        let dt = if self.fixed_step {
+            1.0 / self.fps
+        } else {
+            ctx.input(|input_state| input_state.stable_dt)
+        };
+        self.time = (self.time + dt) % self.duration;
+        ctx.request_repaint();
+    }
+
+    pub(crate) fn draw(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button(if self.playing { "⏸" } else { "▶" }).clicked() {
+                self.playing = !self.playing;
+            }
+            if ui.button("⏮").clicked() {
+                self.time = 0.0;
+            }
+
+            ui.add(
+                egui::Slider::new(&mut self.time, 0.0..=self.duration)
+                    .text("s")
+                    .max_decimals(2),
+            );
+            ui.label("Duration:");
+            ui.add(
+                egui::DragValue::new(&mut self.duration)
+                    .clamp_range(0.1..=f32::MAX)
+                    .speed(0.1)
+                    .suffix(" s"),
+            );
+
+            ui.checkbox(&mut self.fixed_step, "Fixed step");
+            ui.add_enabled(
+                self.fixed_step,
+                egui::DragValue::new(&mut self.fps)
+                    .clamp_range(1.0..=240.0)
+                    .suffix(" fps"),
+            );
+        });
+        self.time = self.time.min(self.duration);
+    }
+}
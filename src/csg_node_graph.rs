@@ -1,24 +1,41 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use eframe::egui;
 use egui_node_graph::{
-    DataTypeTrait, Graph, GraphEditorState, InputId, InputParamKind, NodeDataTrait, NodeId,
-    NodeResponse, NodeTemplateIter, NodeTemplateTrait, OutputId, UserResponseTrait,
+    AnyParameterId, DataTypeTrait, Graph, GraphEditorState, InputId, InputParamKind, NodeDataTrait,
+    NodeId, NodeResponse, NodeTemplateIter, NodeTemplateTrait, OutputId, UserResponseTrait,
     WidgetValueTrait,
 };
 
-use crate::ray_marching::csg::{CSGNode, CSGNodeTemplate, CSGNodeTemplateTrait};
+use crate::animation::{Animation, Interpolation, Keyframe, Track};
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::history::{remap_edits, Edit, History, Param};
+use crate::math_nodes::MathNodeTemplate;
+use crate::node_groups::{GroupInterfaceInput, GroupLibrary, SharedGroupLibrary};
+use crate::ray_marching::csg::builder::{
+    CSGCommandBufferBuilder, POS_STACK_MAX_SIZE, TEXT_ATLAS_MAX_SIZE, VALUE_STACK_MAX_SIZE,
+};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplate, CSGNodeTemplateTrait, ExtrudeTemplate, SDF2DNode,
+    SDF2DNodeTemplate, SDF2DNodeTemplateTrait, VARIADIC_INPUT_NAMES,
+};
+use crate::scene::{
+    SceneConnection, SceneError, SceneGraph, SceneInput, SceneKeyframe, SceneNode, SceneTrack,
+    SceneValue, Snippet,
+};
 
 pub struct NodeData {
     template: NodeTemplate,
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum DataType {
     Scalar,
     Vec3,
     CSGNode,
+    SDF2D,
+    Text,
 }
 
 #[derive(Clone, Debug)]
@@ -26,14 +43,67 @@ pub enum ValueType {
     Scalar(f32),
     Vec3([f32; 3]),
     CSGNode(Option<Box<CSGNode>>),
+    SDF2D(Option<Box<SDF2DNode>>),
+    Text(String),
+}
+
+impl DataType {
+    pub(crate) const ALL: [DataType; 5] = [
+        DataType::Scalar,
+        DataType::Vec3,
+        DataType::CSGNode,
+        DataType::SDF2D,
+        DataType::Text,
+    ];
+
+    /// Value of unconnected inputs of this type without a given default.
+    fn default_value(&self) -> ValueType {
+        match self {
+            DataType::Scalar => ValueType::Scalar(0.0),
+            DataType::Vec3 => ValueType::Vec3([0.0; 3]),
+            DataType::CSGNode => ValueType::CSGNode(None),
+            DataType::SDF2D => ValueType::SDF2D(None),
+            DataType::Text => ValueType::Text(String::new()),
+        }
+    }
+
+    /// Whether inputs of this type can hold a constant, instead of only taking a connection.
+    fn has_constant(&self) -> bool {
+        matches!(self, DataType::Scalar | DataType::Vec3 | DataType::Text)
+    }
+}
+
+/// Type of the ports of a parameter in the editor, which only connects ports of equal types.
+/// Types with a conversion between them compare equal, so that the editor connects them and the
+/// conversion node is inserted afterwards.
+#[derive(Copy, Clone, Debug)]
+pub struct PortType(pub DataType);
+
+impl PartialEq for PortType {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+            || Conversion::between(self.0, other.0).is_some()
+            || Conversion::between(other.0, self.0).is_some()
+    }
+}
+
+// Conversions pair up disjoint types, so this is an equivalence.
+impl Eq for PortType {}
+
+impl From<DataType> for PortType {
+    fn from(data_type: DataType) -> Self {
+        PortType(data_type)
+    }
 }
 
 impl ValueType {
-    fn data_type(&self) -> DataType {
+    pub(crate) fn data_type(&self) -> DataType {
         match self {
             ValueType::Scalar(_) => DataType::Scalar,
             ValueType::Vec3(_) => DataType::Vec3,
             ValueType::CSGNode(_) => DataType::CSGNode,
+            ValueType::SDF2D(_) => DataType::SDF2D,
+            ValueType::Text(_) => DataType::Text,
         }
     }
 
@@ -41,6 +111,10 @@ impl ValueType {
         ValueType::CSGNode(Some(Box::new(value.into())))
     }
 
+    fn sdf_2d(value: impl Into<SDF2DNode>) -> Self {
+        ValueType::SDF2D(Some(Box::new(value.into())))
+    }
+
     pub(crate) fn to_scalar(&self) -> Option<f32> {
         match self {
             ValueType::Scalar(x) => Some(*x),
@@ -55,12 +129,46 @@ impl ValueType {
         }
     }
 
+    pub(crate) fn to_text(&self) -> Option<String> {
+        match self {
+            ValueType::Text(x) => Some(x.clone()),
+            _ => None,
+        }
+    }
+
     pub(crate) fn to_csg_node(&self) -> Option<CSGNode> {
         match self {
             ValueType::CSGNode(Some(x)) => Some(*x.clone()),
             _ => None,
         }
     }
+
+    pub(crate) fn to_sdf_2d(&self) -> Option<SDF2DNode> {
+        match self {
+            ValueType::SDF2D(Some(x)) => Some(*x.clone()),
+            _ => None,
+        }
+    }
+
+    /// Value as saved in scene files, `None` for values only set by connections.
+    fn to_scene_value(&self) -> Option<SceneValue> {
+        match self {
+            ValueType::Scalar(x) => Some(SceneValue::Scalar(*x)),
+            ValueType::Vec3(x) => Some(SceneValue::Vec3(*x)),
+            ValueType::Text(x) => Some(SceneValue::Text(x.clone())),
+            ValueType::CSGNode(_) | ValueType::SDF2D(_) => None,
+        }
+    }
+}
+
+impl From<SceneValue> for ValueType {
+    fn from(value: SceneValue) -> Self {
+        match value {
+            SceneValue::Scalar(x) => ValueType::Scalar(x),
+            SceneValue::Vec3(x) => ValueType::Vec3(x),
+            SceneValue::Text(x) => ValueType::Text(x),
+        }
+    }
 }
 
 impl Default for ValueType {
@@ -73,18 +181,128 @@ impl Default for ValueType {
 pub enum NodeTemplate {
     Root,
     CSGNode(CSGNodeTemplate),
+    SDF2DNode(SDF2DNodeTemplate),
+    Math(MathNodeTemplate),
+    /// Instance of the named group in the [`GroupLibrary`].
+    Group(String),
+    /// Input of a group, used in group definitions.
+    GroupInput(DataType),
+    /// The SDF of a group, used in group definitions like `Root`.
+    GroupOutput,
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct Response;
+impl NodeTemplate {
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            NodeTemplate::Root => "Root",
+            NodeTemplate::CSGNode(template) => template.name(),
+            NodeTemplate::SDF2DNode(template) => template.name(),
+            NodeTemplate::Math(template) => template.name(),
+            NodeTemplate::Group(name) => name,
+            NodeTemplate::GroupInput(DataType::Scalar) => "Group Input (Scalar)",
+            NodeTemplate::GroupInput(DataType::Vec3) => "Group Input (Vec3)",
+            NodeTemplate::GroupInput(DataType::CSGNode) => "Group Input (SDF)",
+            NodeTemplate::GroupInput(DataType::SDF2D) => "Group Input (SDF2D)",
+            NodeTemplate::GroupInput(DataType::Text) => "Group Input (Text)",
+            NodeTemplate::GroupOutput => "Group Output",
+        }
+    }
+
+    /// Whether the user can add and remove copies of the node's first input.
+    pub(crate) fn is_variadic(&self) -> bool {
+        match self {
+            NodeTemplate::CSGNode(template) => template.is_variadic(),
+            NodeTemplate::SDF2DNode(template) => template.is_variadic(),
+            _ => false,
+        }
+    }
+
+    /// Find a template by its name in the node finder.
+    pub(crate) fn from_name(name: &str, groups: &GroupLibrary) -> Option<Self> {
+        AllNodeTemplates::new(groups)
+            .all_kinds()
+            .into_iter()
+            .find(|template| template.name() == name)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Response {
+    AddInput(NodeId),
+    RemoveInput(NodeId),
+    /// Keyframe the constant value of the named input at the current time.
+    SetKeyframe(NodeId, String),
+    /// Open the definition of the named group in the editor.
+    EditGroup(String),
+}
 
 #[derive(Default)]
-pub struct GraphState;
+pub struct GraphState {
+    animation: Animation,
+    /// Time of the animation clock in seconds.
+    time: f32,
+    groups: SharedGroupLibrary,
+    /// Problems found by the last evaluation, by node.
+    diagnostics: Vec<(NodeId, Diagnostic)>,
+}
+
+/// Node inserted to connect an output to an input of another type.
+struct Conversion {
+    template: NodeTemplate,
+    input: &'static str,
+    output: &'static str,
+}
+
+impl Conversion {
+    fn between(output_type: DataType, input_type: DataType) -> Option<Self> {
+        match (output_type, input_type) {
+            (DataType::Scalar, DataType::Vec3) => Some(Conversion {
+                template: NodeTemplate::Math(MathNodeTemplate::Splat),
+                input: "x",
+                output: "value",
+            }),
+            (DataType::SDF2D, DataType::CSGNode) => Some(Conversion {
+                template: NodeTemplate::CSGNode(CSGNodeTemplate::Extrude(ExtrudeTemplate)),
+                input: "profile",
+                output: "SDF",
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Which ports of other types a connection dragged from a port of `data_type` can be dropped on,
+/// `None` if only ports of the same type.
+fn conversion_hint(data_type: DataType, from_output: bool) -> Option<String> {
+    let hints: Vec<String> = DataType::ALL
+        .into_iter()
+        .filter_map(|other_type| {
+            let (output_type, input_type) = if from_output {
+                (data_type, other_type)
+            } else {
+                (other_type, data_type)
+            };
+            let conversion = Conversion::between(output_type, input_type)?;
+            Some(format!(
+                "Connects {} to {} with a {} node",
+                output_type.name(),
+                input_type.name(),
+                conversion.template.name()
+            ))
+        })
+        .collect();
+    (!hints.is_empty()).then(|| hints.join("\n"))
+}
 
 impl DataTypeTrait<GraphState> for DataType {
-    fn data_type_color(&self, user_state: &mut GraphState) -> egui::Color32 {
-        // TODO
-        egui::Color32::from_rgb(100, 20, 20)
+    fn data_type_color(&self, _user_state: &mut GraphState) -> egui::Color32 {
+        match self {
+            DataType::Scalar => egui::Color32::from_rgb(90, 160, 230),
+            DataType::Vec3 => egui::Color32::from_rgb(230, 200, 90),
+            DataType::CSGNode => egui::Color32::from_rgb(220, 100, 70),
+            DataType::SDF2D => egui::Color32::from_rgb(120, 200, 110),
+            DataType::Text => egui::Color32::from_rgb(180, 140, 220),
+        }
     }
 
     fn name(&self) -> Cow<str> {
@@ -92,22 +310,31 @@ impl DataTypeTrait<GraphState> for DataType {
             DataType::Scalar => "Scalar".into(),
             DataType::Vec3 => "Vec3".into(),
             DataType::CSGNode => "SDF".into(),
+            DataType::SDF2D => "SDF2D".into(),
+            DataType::Text => "Text".into(),
         }
     }
 }
 
+impl DataTypeTrait<GraphState> for PortType {
+    fn data_type_color(&self, user_state: &mut GraphState) -> egui::Color32 {
+        self.0.data_type_color(user_state)
+    }
+
+    fn name(&self) -> Cow<str> {
+        self.0.name()
+    }
+}
+
 impl NodeTemplateTrait for NodeTemplate {
     type NodeData = NodeData;
-    type DataType = DataType;
+    type DataType = PortType;
     type ValueType = ValueType;
     type UserState = GraphState;
     type CategoryType = ();
 
     fn node_finder_label(&self, user_state: &mut Self::UserState) -> Cow<str> {
-        match self {
-            NodeTemplate::Root => "Root".into(),
-            NodeTemplate::CSGNode(template) => template.name().into(),
-        }
+        self.name().into()
     }
 
     fn node_graph_label(&self, user_state: &mut Self::UserState) -> String {
@@ -131,7 +358,7 @@ impl NodeTemplateTrait for NodeTemplate {
                 graph.add_input_param(
                     node_id,
                     "SDF".to_string(),
-                    DataType::CSGNode,
+                    DataType::CSGNode.into(),
                     ValueType::CSGNode(None),
                     InputParamKind::ConnectionOnly,
                     true,
@@ -142,19 +369,117 @@ impl NodeTemplateTrait for NodeTemplate {
                     graph.add_input_param(
                         node_id,
                         name.to_string(),
-                        value_type.data_type(),
+                        value_type.data_type().into(),
                         value_type,
                         kind,
                         true,
                     );
                 }
-                graph.add_output_param(node_id, "SDF".to_string(), DataType::CSGNode);
+                graph.add_output_param(node_id, "SDF".to_string(), DataType::CSGNode.into());
             }
+            NodeTemplate::SDF2DNode(template) => {
+                for (name, value_type, kind) in template.input_params() {
+                    graph.add_input_param(
+                        node_id,
+                        name.to_string(),
+                        value_type.data_type().into(),
+                        value_type,
+                        kind,
+                        true,
+                    );
+                }
+                graph.add_output_param(node_id, "SDF2D".to_string(), DataType::SDF2D.into());
+            }
+            NodeTemplate::Math(template) => {
+                for (name, value_type, kind) in template.input_params() {
+                    graph.add_input_param(
+                        node_id,
+                        name.to_string(),
+                        value_type.data_type().into(),
+                        value_type,
+                        kind,
+                        true,
+                    );
+                }
+                for (name, data_type) in template.outputs() {
+                    graph.add_output_param(node_id, name.to_string(), data_type.into());
+                }
+            }
+            NodeTemplate::Group(name) => {
+                for input in user_state.groups.borrow().interface(name) {
+                    add_group_input(graph, node_id, &input);
+                }
+                graph.add_output_param(node_id, "SDF".to_string(), DataType::CSGNode.into());
+            }
+            NodeTemplate::GroupInput(data_type) => {
+                graph.add_input_param(
+                    node_id,
+                    "name".to_string(),
+                    DataType::Text.into(),
+                    ValueType::Text(data_type.name().to_lowercase()),
+                    InputParamKind::ConstantOnly,
+                    true,
+                );
+                if data_type.has_constant() {
+                    graph.add_input_param(
+                        node_id,
+                        "default".to_string(),
+                        (*data_type).into(),
+                        data_type.default_value(),
+                        InputParamKind::ConstantOnly,
+                        true,
+                    );
+                }
+                graph.add_output_param(node_id, "value".to_string(), (*data_type).into());
+            }
+            NodeTemplate::GroupOutput => {
+                graph.add_input_param(
+                    node_id,
+                    "SDF".to_string(),
+                    DataType::CSGNode.into(),
+                    ValueType::CSGNode(None),
+                    InputParamKind::ConnectionOnly,
+                    true,
+                );
+            }
+        }
+    }
+}
+
+/// Add an input of a group to a node of the group.
+fn add_group_input(graph: &mut MyGraph, node_id: NodeId, input: &GroupInterfaceInput) {
+    let value = input
+        .default
+        .clone()
+        .map_or_else(|| input.data_type.default_value(), ValueType::from);
+    let kind = if input.data_type.has_constant() {
+        InputParamKind::ConnectionOrConstant
+    } else {
+        InputParamKind::ConnectionOnly
+    };
+    graph.add_input_param(
+        node_id,
+        input.name.clone(),
+        input.data_type.into(),
+        value,
+        kind,
+        true,
+    );
+}
+
+pub struct AllNodeTemplates {
+    /// Names of the groups in the library.
+    groups: Vec<String>,
+}
+
+impl AllNodeTemplates {
+    fn new(groups: &GroupLibrary) -> Self {
+        Self {
+            groups: groups.names().cloned().collect(),
         }
     }
 }
 
-pub struct AllNodeTemplates;
 impl NodeTemplateIter for AllNodeTemplates {
     type Item = NodeTemplate;
 
@@ -165,6 +490,15 @@ impl NodeTemplateIter for AllNodeTemplates {
                 .into_iter()
                 .map(NodeTemplate::CSGNode),
         );
+        all_templates.extend(
+            SDF2DNodeTemplate::all()
+                .into_iter()
+                .map(NodeTemplate::SDF2DNode),
+        );
+        all_templates.extend(MathNodeTemplate::all().into_iter().map(NodeTemplate::Math));
+        all_templates.extend(DataType::ALL.into_iter().map(NodeTemplate::GroupInput));
+        all_templates.push(NodeTemplate::GroupOutput);
+        all_templates.extend(self.groups.iter().cloned().map(NodeTemplate::Group));
         all_templates
     }
 }
@@ -177,20 +511,39 @@ impl WidgetValueTrait for ValueType {
     fn value_widget(
         &mut self,
         param_name: &str,
-        _node_id: NodeId,
+        node_id: NodeId,
         ui: &mut egui::Ui,
-        _user_state: &mut Self::UserState,
+        user_state: &mut Self::UserState,
         _node_data: &Self::NodeData,
     ) -> Vec<Self::Response> {
+        let mut responses = Vec::default();
+        let mut keyframe_button = |ui: &mut egui::Ui| {
+            let keyed = user_state
+                .animation
+                .track(node_id, param_name)
+                .is_some_and(|track| track.has_keyframe_at(user_state.time));
+            if ui
+                .small_button(if keyed { "◆" } else { "◇" })
+                .on_hover_text("Set keyframe at the current time")
+                .clicked()
+            {
+                responses.push(Response::SetKeyframe(node_id, param_name.to_string()));
+            }
+        };
+
         match self {
             ValueType::Scalar(value) => {
                 ui.horizontal(|ui| {
+                    keyframe_button(ui);
                     ui.label(param_name);
                     ui.add(egui::DragValue::new(value))
                 });
             }
             ValueType::Vec3(value) => {
-                ui.label(param_name);
+                ui.horizontal(|ui| {
+                    keyframe_button(ui);
+                    ui.label(param_name);
+                });
                 ui.horizontal(|ui| {
                     ui.label("x");
                     ui.add(egui::DragValue::new(&mut value[0]));
@@ -200,11 +553,17 @@ impl WidgetValueTrait for ValueType {
                     ui.add(egui::DragValue::new(&mut value[2]));
                 });
             }
-            ValueType::CSGNode(_) => {
+            ValueType::Text(value) => {
+                ui.horizontal(|ui| {
+                    ui.label(param_name);
+                    ui.text_edit_singleline(value)
+                });
+            }
+            ValueType::CSGNode(_) | ValueType::SDF2D(_) => {
                 ui.label(param_name);
             }
         }
-        Vec::default()
+        responses
     }
 }
 
@@ -212,7 +571,7 @@ impl UserResponseTrait for Response {}
 impl NodeDataTrait for NodeData {
     type Response = Response;
     type UserState = GraphState;
-    type DataType = DataType;
+    type DataType = PortType;
     type ValueType = ValueType;
 
     fn bottom_ui(
@@ -225,85 +584,1724 @@ impl NodeDataTrait for NodeData {
     where
         Self::Response: UserResponseTrait,
     {
-        Vec::default()
+        let mut responses = Vec::default();
+
+        if self.template.is_variadic() {
+            let input_count = variadic_input_count(graph, node_id);
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        input_count < VARIADIC_INPUT_NAMES.len(),
+                        egui::Button::new("+"),
+                    )
+                    .clicked()
+                {
+                    responses.push(NodeResponse::User(Response::AddInput(node_id)));
+                }
+                if ui
+                    .add_enabled(input_count > 2, egui::Button::new("-"))
+                    .clicked()
+                {
+                    responses.push(NodeResponse::User(Response::RemoveInput(node_id)));
+                }
+            });
+        }
+        if let NodeTemplate::Group(name) = &self.template {
+            if ui.button("Edit group").clicked() {
+                responses.push(NodeResponse::User(Response::EditGroup(name.clone())));
+            }
+        }
+
+        responses
+    }
+
+    fn top_bar_ui(
+        &self,
+        ui: &mut egui::Ui,
+        node_id: NodeId,
+        _graph: &Graph<Self, Self::DataType, Self::ValueType>,
+        user_state: &mut Self::UserState,
+    ) -> Vec<NodeResponse<Self::Response, Self>>
+    where
+        Self::Response: UserResponseTrait,
+    {
+        let messages: Vec<String> = user_state
+            .diagnostics
+            .iter()
+            .filter(|(diagnostic_node_id, _)| *diagnostic_node_id == node_id)
+            .map(|(_, diagnostic)| diagnostic.to_string())
+            .collect();
+        if !messages.is_empty() {
+            ui.label(egui::RichText::new("⚠").color(ui.visuals().error_fg_color))
+                .on_hover_text(messages.join("\n"));
+        }
+
+        Vec::new()
+    }
+
+    fn titlebar_color(
+        &self,
+        ui: &egui::Ui,
+        node_id: NodeId,
+        _graph: &Graph<Self, Self::DataType, Self::ValueType>,
+        user_state: &mut Self::UserState,
+    ) -> Option<egui::Color32> {
+        user_state
+            .diagnostics
+            .iter()
+            .any(|(diagnostic_node_id, _)| *diagnostic_node_id == node_id)
+            .then(|| ui.visuals().error_fg_color.gamma_multiply(0.4))
     }
 }
 
-type MyGraph = Graph<NodeData, DataType, ValueType>;
-type MyEditorState = GraphEditorState<NodeData, DataType, ValueType, NodeTemplate, GraphState>;
+type MyGraph = Graph<NodeData, PortType, ValueType>;
+
+/// Number of variadic inputs of a variadic node, these are the leading inputs sharing the type of
+/// its first input.
+fn variadic_input_count(graph: &MyGraph, node_id: NodeId) -> usize {
+    let inputs = &graph[node_id].inputs;
+    let first_type = &graph.get_input(inputs[0].1).typ.0;
+    inputs
+        .iter()
+        .take_while(|(_, input_id)| &graph.get_input(*input_id).typ.0 == first_type)
+        .count()
+}
+
+fn input_name(graph: &MyGraph, input_id: InputId) -> &str {
+    let (name, _) = graph[graph.get_input(input_id).node]
+        .inputs
+        .iter()
+        .find(|(_, id)| *id == input_id)
+        .unwrap();
+    name
+}
+
+fn output_name(graph: &MyGraph, output_id: OutputId) -> &str {
+    let (name, _) = graph[graph.get_output(output_id).node]
+        .outputs
+        .iter()
+        .find(|(_, id)| *id == output_id)
+        .unwrap();
+    name
+}
+
+fn input_param(graph: &MyGraph, input_id: InputId) -> Param {
+    Param {
+        node_id: graph.get_input(input_id).node,
+        name: input_name(graph, input_id).to_string(),
+    }
+}
+
+fn output_param(graph: &MyGraph, output_id: OutputId) -> Param {
+    Param {
+        node_id: graph.get_output(output_id).node,
+        name: output_name(graph, output_id).to_string(),
+    }
+}
+type MyEditorState = GraphEditorState<NodeData, PortType, ValueType, NodeTemplate, GraphState>;
+
+pub(crate) const UNDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+pub(crate) const REDO_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::Z,
+);
+/// Shown in the menu only, egui turns these into copy and cut events.
+pub(crate) const COPY_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::C);
+pub(crate) const CUT_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::X);
+pub(crate) const DUPLICATE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::D);
+
+/// Offset of pasted and duplicated nodes from the copied nodes, so that they don't hide them.
+const PASTE_OFFSET: egui::Vec2 = egui::vec2(40.0, 40.0);
+
+/// State of the graph before the editor handles input, to find out what the input changed.
+struct GraphSnapshot {
+    /// Node, name and constant value of every input.
+    inputs: HashMap<InputId, (Param, Option<SceneValue>)>,
+    outputs: HashMap<OutputId, Param>,
+    connections: HashMap<InputId, OutputId>,
+    positions: HashMap<NodeId, egui::Pos2>,
+}
+
+impl GraphSnapshot {
+    fn new(editor_state: &MyEditorState) -> Self {
+        let graph = &editor_state.graph;
+        let mut inputs = HashMap::new();
+        let mut outputs = HashMap::new();
+        for (node_id, node) in &graph.nodes {
+            for (name, input_id) in &node.inputs {
+                let param = Param {
+                    node_id,
+                    name: name.clone(),
+                };
+                let value = graph.get_input(*input_id).value.to_scene_value();
+                inputs.insert(*input_id, (param, value));
+            }
+            for (name, output_id) in &node.outputs {
+                let param = Param {
+                    node_id,
+                    name: name.clone(),
+                };
+                outputs.insert(*output_id, param);
+            }
+        }
+
+        Self {
+            inputs,
+            outputs,
+            connections: graph.iter_connections().collect(),
+            positions: editor_state
+                .node_positions
+                .iter()
+                .map(|(node_id, pos)| (node_id, *pos))
+                .collect(),
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct CSGNodeGraph {
     pub editor_state: MyEditorState,
     user_state: GraphState,
+    history: History,
+    /// Group whose "Edit group" button was clicked.
+    group_to_edit: Option<String>,
+    /// Result of an operation started from the keyboard, for the status bar.
+    status: Option<String>,
 }
 
 impl CSGNodeGraph {
-    pub fn draw(&mut self, ui: &mut egui::Ui) {
-        let _graph_response = self.editor_state.draw_graph_editor(
+    pub(crate) fn new(groups: SharedGroupLibrary) -> Self {
+        let mut node_graph = Self::default();
+        node_graph.user_state.groups = groups;
+        node_graph
+    }
+
+    /// Name of the group to open in the editor, requested while drawing the graph.
+    pub(crate) fn take_group_to_edit(&mut self) -> Option<String> {
+        self.group_to_edit.take()
+    }
+
+    pub(crate) fn take_status(&mut self) -> Option<String> {
+        self.status.take()
+    }
+
+    /// Draw the node graph, with `time` in seconds for keyframing.
+    pub fn draw(&mut self, ui: &mut egui::Ui, time: f32) {
+        if time != self.user_state.time {
+            self.user_state.time = time;
+            self.apply_animation();
+        }
+
+        // Text fields have their own undo.
+        if ui.ctx().memory(|memory| memory.focus().is_none()) {
+            if ui.input_mut(|input_state| input_state.consume_shortcut(&REDO_SHORTCUT)) {
+                self.redo();
+            } else if ui.input_mut(|input_state| input_state.consume_shortcut(&UNDO_SHORTCUT)) {
+                self.undo();
+            } else if ui.input_mut(|input_state| input_state.consume_shortcut(&DUPLICATE_SHORTCUT))
+            {
+                self.duplicate_selection();
+            }
+
+            let events = ui.input(|input_state| input_state.events.clone());
+            for event in events {
+                match event {
+                    egui::Event::Copy => {
+                        if let Some(text) = self.copy_selection() {
+                            ui.ctx().output_mut(|output| output.copied_text = text);
+                        }
+                    }
+                    egui::Event::Cut => {
+                        if let Some(text) = self.cut_selection() {
+                            ui.ctx().output_mut(|output| output.copied_text = text);
+                        }
+                    }
+                    egui::Event::Paste(text) => {
+                        if let Err(err) = self.paste(&text) {
+                            self.status = Some(format!("Failed to paste nodes: {err}"));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let snapshot = GraphSnapshot::new(&self.editor_state);
+        if let Some((_, param)) = self.editor_state.connection_in_progress {
+            let graph = &self.editor_state.graph;
+            let from_output = matches!(param, AnyParameterId::Output(_));
+            if let Some(hint) = (graph.any_param_type(param).ok())
+                .and_then(|port_type| conversion_hint(port_type.0, from_output))
+            {
+                egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("conversion_hint"), |ui| {
+                    ui.label(hint)
+                });
+            }
+        }
+        let all_templates = AllNodeTemplates::new(&self.user_state.groups.borrow());
+        let graph_response = self.editor_state.draw_graph_editor(
             ui,
-            AllNodeTemplates,
+            all_templates,
             &mut self.user_state,
             Vec::default(),
         );
+
+        let mut edits = Vec::new();
+        for node_response in graph_response.node_responses {
+            match node_response {
+                NodeResponse::User(Response::AddInput(node_id)) => {
+                    let input_id = self.add_variadic_input(node_id);
+                    let value = self
+                        .editor_state
+                        .graph
+                        .get_input(input_id)
+                        .value
+                        .to_scene_value();
+                    edits.push(Edit::AddInput { node_id, value });
+                }
+                NodeResponse::User(Response::RemoveInput(node_id)) => {
+                    edits.extend(self.remove_variadic_input_edits(node_id));
+                    self.remove_variadic_input(node_id);
+                }
+                NodeResponse::User(Response::SetKeyframe(node_id, param_name)) => {
+                    let old = self
+                        .user_state
+                        .animation
+                        .track(node_id, &param_name)
+                        .cloned();
+                    self.set_keyframe(node_id, &param_name);
+                    let new = self
+                        .user_state
+                        .animation
+                        .track(node_id, &param_name)
+                        .cloned();
+                    let input = Param {
+                        node_id,
+                        name: param_name,
+                    };
+                    edits.push(Edit::SetTrack { input, old, new });
+                }
+                NodeResponse::User(Response::EditGroup(name)) => self.group_to_edit = Some(name),
+                NodeResponse::CreatedNode(node_id) => edits.push(Edit::AddNode {
+                    node_id,
+                    node: self.scene_node(node_id),
+                    tracks: Vec::new(),
+                }),
+                NodeResponse::DeleteNodeFull { node_id, node } => {
+                    let tracks = self.node_tracks(node_id);
+                    self.user_state.animation.remove_node(node_id);
+
+                    let [x, y] = snapshot.positions[&node_id].into();
+                    let node = SceneNode {
+                        template: node.user_data.template.name().to_string(),
+                        position: [x, y],
+                        inputs: node
+                            .inputs
+                            .iter()
+                            .map(|(name, input_id)| SceneInput {
+                                name: name.clone(),
+                                value: snapshot.inputs[input_id].1.clone(),
+                            })
+                            .collect(),
+                    };
+                    edits.push(Edit::RemoveNode {
+                        node_id,
+                        node,
+                        tracks,
+                    });
+                }
+                NodeResponse::ConnectEventEnded { output, input } => {
+                    let graph = &self.editor_state.graph;
+                    if graph.get_output(output).typ.0 != graph.get_input(input).typ.0 {
+                        let previous_output = snapshot.connections.get(&input).copied();
+                        edits.extend(self.convert_connection(output, input, previous_output));
+                        continue;
+                    }
+                    // Connecting to an input replaces its connection.
+                    if let Some(previous_output) = snapshot.connections.get(&input) {
+                        if *previous_output != output {
+                            edits.push(Edit::Disconnect {
+                                output: snapshot.outputs[previous_output].clone(),
+                                input: snapshot.inputs[&input].0.clone(),
+                            });
+                        }
+                    }
+                    edits.push(Edit::Connect {
+                        output: snapshot.outputs[&output].clone(),
+                        input: snapshot.inputs[&input].0.clone(),
+                    });
+                }
+                NodeResponse::DisconnectEvent { output, input } => {
+                    edits.push(Edit::Disconnect {
+                        output: snapshot.outputs[&output].clone(),
+                        input: snapshot.inputs[&input].0.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Moving nodes also moves the other selected nodes without a response, so compare
+        // positions instead of handling `NodeResponse::MoveNode`.
+        // Edits are in the order of the graph, so the edits of consecutive frames of a drag match
+        // up for merging.
+        for (node_id, new_pos) in &self.editor_state.node_positions {
+            if let Some(old_pos) = snapshot.positions.get(&node_id) {
+                if new_pos != old_pos {
+                    edits.push(Edit::MoveNode {
+                        node_id,
+                        delta: *new_pos - *old_pos,
+                    });
+                }
+            }
+        }
+
+        // Value widgets don't report changes, so compare values.
+        for (input_id, param) in &self.editor_state.graph.inputs {
+            let Some((input, old)) = snapshot.inputs.get(&input_id) else {
+                continue;
+            };
+            if let (Some(old), Some(new)) = (old, param.value.to_scene_value()) {
+                if *old != new {
+                    edits.push(Edit::SetValue {
+                        input: input.clone(),
+                        old: old.clone(),
+                        new,
+                    });
+                }
+            }
+        }
+
+        let ongoing = ui.input(|input_state| input_state.pointer.any_down())
+            || ui.ctx().memory(|memory| memory.focus().is_some());
+        self.history.record(edits, ongoing);
+    }
+
+    /// Replace a connection the editor made between ports of different types, see [`PortType`],
+    /// by a conversion node connected to both, and return the edits. Without a conversion the
+    /// connection is undone.
+    fn convert_connection(
+        &mut self,
+        output_id: OutputId,
+        input_id: InputId,
+        previous_output: Option<OutputId>,
+    ) -> Vec<Edit> {
+        let graph = &mut self.editor_state.graph;
+        let output_type = graph.get_output(output_id).typ.0;
+        let input_type = graph.get_input(input_id).typ.0;
+        let output = output_param(graph, output_id);
+        let input = input_param(graph, input_id);
+        // Restore the input's connection from before the editor replaced it.
+        graph.remove_connection(input_id);
+        if let Some(previous_output) = previous_output {
+            graph.add_connection(previous_output, input_id);
+        }
+
+        let Some(conversion) = Conversion::between(output_type, input_type) else {
+            self.status = Some(format!(
+                "Can't connect {} to {}",
+                output_type.name(),
+                input_type.name()
+            ));
+            return Vec::new();
+        };
+
+        let positions = &self.editor_state.node_positions;
+        let position = positions[output.node_id].lerp(positions[input.node_id], 0.5);
+        let scene_node = SceneNode {
+            template: conversion.template.name().to_string(),
+            position: position.into(),
+            inputs: Vec::new(),
+        };
+        let Ok(node_id) = self.add_scene_node(0, &scene_node) else {
+            return Vec::new();
+        };
+
+        let graph = &self.editor_state.graph;
+        let mut edits = vec![Edit::AddNode {
+            node_id,
+            node: self.scene_node(node_id),
+            tracks: Vec::new(),
+        }];
+        // Connecting to an input replaces its connection.
+        if let Some(previous_output) = graph[input.node_id]
+            .get_input(&input.name)
+            .ok()
+            .and_then(|input_id| graph.connection(input_id))
+        {
+            edits.push(Edit::Disconnect {
+                output: output_param(graph, previous_output),
+                input: input.clone(),
+            });
+        }
+        edits.push(Edit::Connect {
+            output,
+            input: Param {
+                node_id,
+                name: conversion.input.to_string(),
+            },
+        });
+        edits.push(Edit::Connect {
+            output: Param {
+                node_id,
+                name: conversion.output.to_string(),
+            },
+            input,
+        });
+        // The node is already added.
+        self.apply_edits(&mut edits[1..]);
+        edits
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    pub(crate) fn undo(&mut self) {
+        if let Some(step) = self.history.pop_undo() {
+            let mut edits: Vec<Edit> = step.into_iter().rev().map(Edit::inverse).collect();
+            self.apply_edits(&mut edits);
+            let step = edits.into_iter().rev().map(Edit::inverse).collect();
+            self.history.push_redo(step);
+        }
+    }
+
+    pub(crate) fn redo(&mut self) {
+        if let Some(mut step) = self.history.pop_redo() {
+            self.apply_edits(&mut step);
+            self.history.push_undo(step);
+        }
+    }
+
+    fn apply_edits(&mut self, edits: &mut [Edit]) {
+        for i in 0..edits.len() {
+            if let Some((old, new)) = self.apply_edit(&edits[i]) {
+                remap_edits(edits, old, new);
+                self.history.remap(old, new);
+            }
+        }
+        self.apply_animation();
+    }
+
+    fn remove_node(&mut self, node_id: NodeId) {
+        self.editor_state.graph.remove_node(node_id);
+        self.editor_state.node_positions.remove(node_id);
+        self.editor_state.node_order.retain(|id| *id != node_id);
+        self.editor_state.selected_nodes.retain(|id| *id != node_id);
+        self.user_state.animation.remove_node(node_id);
+    }
+
+    /// Apply an edit, and return the old and new id of a node restored under a new id.
+    fn apply_edit(&mut self, edit: &Edit) -> Option<(NodeId, NodeId)> {
+        let graph = &mut self.editor_state.graph;
+        // Inputs of group nodes can disappear when the group's definition changes, edits of those
+        // are skipped.
+        let input_id =
+            |graph: &MyGraph, input: &Param| graph[input.node_id].get_input(&input.name).ok();
+        let output_id =
+            |graph: &MyGraph, output: &Param| graph[output.node_id].get_output(&output.name).ok();
+
+        match edit {
+            Edit::AddNode {
+                node_id,
+                node,
+                tracks,
+            } => {
+                let Ok(new_node_id) = self.add_scene_node(0, node) else {
+                    // The node's group was removed from the library.
+                    return None;
+                };
+                for (param_name, track) in tracks {
+                    self.user_state
+                        .animation
+                        .insert_track(new_node_id, param_name, track.clone());
+                }
+                return Some((*node_id, new_node_id));
+            }
+            Edit::RemoveNode { node_id, .. } => self.remove_node(*node_id),
+            Edit::Connect { output, input } => {
+                if let (Some(output_id), Some(input_id)) =
+                    (output_id(graph, output), input_id(graph, input))
+                {
+                    graph.add_connection(output_id, input_id);
+                }
+            }
+            Edit::Disconnect { input, .. } => {
+                if let Some(input_id) = input_id(graph, input) {
+                    graph.remove_connection(input_id);
+                }
+            }
+            Edit::SetValue { input, new, .. } => {
+                if let Some(input_id) = input_id(graph, input) {
+                    graph.inputs[input_id].value = new.clone().into();
+                }
+            }
+            Edit::MoveNode { node_id, delta } => {
+                self.editor_state.node_positions[*node_id] += *delta;
+            }
+            Edit::AddInput { node_id, value } => {
+                let input_id = self.add_variadic_input(*node_id);
+                if let Some(value) = value {
+                    self.editor_state.graph.inputs[input_id].value = value.clone().into();
+                }
+            }
+            Edit::RemoveInput { node_id, .. } => self.remove_variadic_input(*node_id),
+            Edit::SetTrack { input, new, .. } => match new {
+                Some(track) => self.user_state.animation.insert_track(
+                    input.node_id,
+                    &input.name,
+                    track.clone(),
+                ),
+                None => self
+                    .user_state
+                    .animation
+                    .remove_track(input.node_id, &input.name),
+            },
+        }
+        None
+    }
+
+    /// Edits undone by removing the last variadic input of a node: the input itself, its
+    /// connection and its keyframes.
+    fn remove_variadic_input_edits(&self, node_id: NodeId) -> Vec<Edit> {
+        let graph = &self.editor_state.graph;
+        let input_count = variadic_input_count(graph, node_id);
+        let (name, input_id) = graph[node_id].inputs[input_count - 1].clone();
+        let input = Param { node_id, name };
+
+        let mut edits = Vec::new();
+        if let Some(output_id) = graph.connection(input_id) {
+            edits.push(Edit::Disconnect {
+                output: output_param(graph, output_id),
+                input: input.clone(),
+            });
+        }
+        if let Some(track) = self.user_state.animation.track(node_id, &input.name) {
+            edits.push(Edit::SetTrack {
+                input,
+                old: Some(track.clone()),
+                new: None,
+            });
+        }
+        edits.push(Edit::RemoveInput {
+            node_id,
+            value: graph.get_input(input_id).value.to_scene_value(),
+        });
+        edits
+    }
+
+    fn set_keyframe(&mut self, node_id: NodeId, param_name: &str) {
+        let graph = &self.editor_state.graph;
+        let input_id = graph[node_id].get_input(param_name).unwrap();
+        let value = graph.get_input(input_id).value.clone();
+        self.user_state
+            .animation
+            .track_mut(node_id, param_name)
+            .set_keyframe(self.user_state.time, value);
+    }
+
+    /// Show the animated values at the current time in the input widgets, so they can be edited
+    /// and keyframed again.
+    fn apply_animation(&mut self) {
+        let graph = &mut self.editor_state.graph;
+        for ((node_id, param_name), track) in self.user_state.animation.tracks() {
+            let Ok(input_id) = graph[*node_id].get_input(param_name) else {
+                continue;
+            };
+            if let Some(value) = track.sample(self.user_state.time) {
+                graph.inputs[input_id].value = value;
+            }
+        }
+    }
+
+    /// Draw the keyframes of all animated inputs, `duration` is the length of the timeline.
+    pub fn draw_timeline(&mut self, ui: &mut egui::Ui, duration: f32) {
+        let graph = &self.editor_state.graph;
+        let animation = &mut self.user_state.animation;
+
+        let mut tracks: Vec<_> = animation
+            .tracks()
+            .map(|((node_id, param_name), _)| {
+                let label = format!("{} · {}", graph[*node_id].label, param_name);
+                (label, *node_id, param_name.clone())
+            })
+            .collect();
+        tracks.sort();
+
+        ui.heading("Timeline");
+        if tracks.is_empty() {
+            ui.label("Click ◇ next to a node input to keyframe it.");
+        }
+
+        // Tracks before they were changed.
+        let mut changed_tracks = Vec::new();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (label, node_id, param_name) in tracks {
+                let track = animation.track_mut(node_id, &param_name);
+                let old_track = track.clone();
+                let mut changed = false;
+                ui.collapsing(label, |ui| {
+                    draw_track_strip(ui, track, self.user_state.time, duration);
+
+                    egui::Grid::new((node_id, &param_name)).show(ui, |ui| {
+                        // Edits are applied after the loop, as moving a keyframe re-sorts them.
+                        let mut times = Vec::new();
+                        let mut interpolations = Vec::new();
+                        let mut removed = None;
+                        for (i, keyframe) in track.keyframes().iter().enumerate() {
+                            let mut time = keyframe.time;
+                            if ui
+                                .add(egui::DragValue::new(&mut time).speed(0.01).suffix(" s"))
+                                .changed()
+                            {
+                                times.push((i, time.max(0.0)));
+                            }
+
+                            ui.label(match &keyframe.value {
+                                ValueType::Scalar(x) => format!("{x:.3}"),
+                                ValueType::Vec3([x, y, z]) => format!("{x:.3}, {y:.3}, {z:.3}"),
+                                _ => unreachable!("only scalars and vectors are animated"),
+                            });
+
+                            let mut interpolation = keyframe.interpolation;
+                            egui::ComboBox::from_id_source((node_id, &param_name, i))
+                                .selected_text(interpolation.name())
+                                .show_ui(ui, |ui| {
+                                    for option in Interpolation::ALL {
+                                        ui.selectable_value(
+                                            &mut interpolation,
+                                            option,
+                                            option.name(),
+                                        );
+                                    }
+                                });
+                            if interpolation != keyframe.interpolation {
+                                interpolations.push((i, interpolation));
+                            }
+
+                            if ui.small_button("🗑").clicked() {
+                                removed = Some(i);
+                            }
+                            ui.end_row();
+                        }
+
+                        changed = !times.is_empty() || !interpolations.is_empty();
+                        for (i, interpolation) in interpolations {
+                            track.set_interpolation(i, interpolation);
+                        }
+                        if let Some(removed) = removed {
+                            track.remove_keyframe(removed);
+                            times.retain(|(i, _)| *i != removed);
+                            for (i, _) in &mut times {
+                                if *i > removed {
+                                    *i -= 1;
+                                }
+                            }
+                            changed = true;
+                        }
+                        track.set_keyframe_times(&times);
+                    });
+                });
+                if changed {
+                    changed_tracks.push((node_id, param_name, old_track));
+                }
+            }
+        });
+
+        if !changed_tracks.is_empty() {
+            self.user_state.animation.remove_empty_tracks();
+            self.apply_animation();
+        }
+
+        let edits = changed_tracks
+            .into_iter()
+            .map(|(node_id, name, old)| {
+                let new = self.user_state.animation.track(node_id, &name).cloned();
+                Edit::SetTrack {
+                    input: Param { node_id, name },
+                    old: Some(old),
+                    new,
+                }
+            })
+            .collect();
+        let ongoing = ui.input(|input_state| input_state.pointer.any_down());
+        self.history.record(edits, ongoing);
+    }
+
+    fn add_variadic_input(&mut self, node_id: NodeId) -> InputId {
+        let graph = &mut self.editor_state.graph;
+        let input_count = variadic_input_count(graph, node_id);
+        // New inputs copy the last variadic input, e.g. a new control point starts at the last one.
+        let (_, last_input_id) = graph[node_id].inputs[input_count - 1];
+        let last_input = graph.get_input(last_input_id);
+        let value = last_input.value.clone();
+        let kind = last_input.kind;
+        let input_id = graph.add_input_param(
+            node_id,
+            VARIADIC_INPUT_NAMES[input_count].to_string(),
+            value.data_type().into(),
+            value,
+            kind,
+            true,
+        );
+
+        // Move the new input from the end to after the other variadic inputs.
+        let inputs = &mut graph[node_id].inputs;
+        let input = inputs.pop().unwrap();
+        inputs.insert(input_count, input);
+        input_id
+    }
+
+    fn remove_variadic_input(&mut self, node_id: NodeId) {
+        let graph = &mut self.editor_state.graph;
+        let input_count = variadic_input_count(graph, node_id);
+        let (param_name, input_id) = graph[node_id].inputs[input_count - 1].clone();
+        graph.remove_input_param(input_id);
+        self.user_state.animation.remove_track(node_id, &param_name);
+    }
+
+    /// The graph in the form saved to scene files.
+    pub(crate) fn to_scene_graph(&self) -> SceneGraph {
+        let node_ids: Vec<NodeId> = self.editor_state.graph.iter_nodes().collect();
+        self.scene_graph_of(&node_ids)
+    }
+
+    /// The given nodes in the form saved to scene files, with the connections between them.
+    fn scene_graph_of(&self, node_ids: &[NodeId]) -> SceneGraph {
+        let graph = &self.editor_state.graph;
+        let node_indices: HashMap<NodeId, usize> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(index, node_id)| (*node_id, index))
+            .collect();
+
+        let nodes = node_ids
+            .iter()
+            .map(|&node_id| self.scene_node(node_id))
+            .collect();
+
+        let connections = graph
+            .iter_connections()
+            .filter_map(|(input_id, output_id)| {
+                Some(SceneConnection {
+                    from: *node_indices.get(&graph.get_output(output_id).node)?,
+                    output: output_name(graph, output_id).to_string(),
+                    to: *node_indices.get(&graph.get_input(input_id).node)?,
+                    input: input_name(graph, input_id).to_string(),
+                })
+            })
+            .collect();
+
+        let mut tracks: Vec<SceneTrack> = self
+            .user_state
+            .animation
+            .tracks()
+            .filter(|((node_id, _), _)| node_indices.contains_key(node_id))
+            .map(|((node_id, param_name), track)| SceneTrack {
+                node: node_indices[node_id],
+                input: param_name.clone(),
+                keyframes: track
+                    .keyframes()
+                    .iter()
+                    .map(|keyframe| SceneKeyframe {
+                        time: keyframe.time,
+                        value: keyframe.value.to_scene_value().unwrap(),
+                        interpolation: keyframe.interpolation,
+                    })
+                    .collect(),
+            })
+            .collect();
+        // Tracks are stored in a hash map, sort them so saving the same graph gives the same file.
+        tracks.sort_by(|a, b| (a.node, &a.input).cmp(&(b.node, &b.input)));
+
+        SceneGraph {
+            nodes,
+            connections,
+            tracks,
+        }
     }
 
-    pub fn evaluate_root(&self) -> Option<CSGNode> {
-        let (_, root_node) = self
+    /// Keyframe tracks of the inputs of a node, by input name.
+    fn node_tracks(&self, node_id: NodeId) -> Vec<(String, Track)> {
+        self.user_state
+            .animation
+            .tracks()
+            .filter(|((track_node_id, _), _)| *track_node_id == node_id)
+            .map(|((_, param_name), track)| (param_name.clone(), track.clone()))
+            .collect()
+    }
+
+    fn scene_node(&self, node_id: NodeId) -> SceneNode {
+        let graph = &self.editor_state.graph;
+        let node = &graph[node_id];
+        let position = self
             .editor_state
-            .graph
+            .node_positions
+            .get(node_id)
+            .map_or([0.0; 2], |pos| [pos.x, pos.y]);
+        let inputs = node
+            .inputs
+            .iter()
+            .map(|(name, input_id)| SceneInput {
+                name: name.clone(),
+                value: graph.get_input(*input_id).value.to_scene_value(),
+            })
+            .collect();
+        SceneNode {
+            template: node.user_data.template.name().to_string(),
+            position,
+            inputs,
+        }
+    }
+
+    /// Build a graph from its saved form, checking that connections and keyframes fit the nodes.
+    pub(crate) fn from_scene_graph(
+        scene_graph: &SceneGraph,
+        groups: SharedGroupLibrary,
+    ) -> Result<Self, SceneError> {
+        let mut node_graph = Self::new(groups);
+        node_graph.add_scene_graph(scene_graph)?;
+        Ok(node_graph)
+    }
+
+    /// Add the nodes of a scene graph to the graph, and return their ids in the order of the scene
+    /// graph. Nothing is added if the scene graph is invalid.
+    fn add_scene_graph(&mut self, scene_graph: &SceneGraph) -> Result<Vec<NodeId>, SceneError> {
+        let mut node_ids = Vec::new();
+        let result = self.add_scene_graph_into(scene_graph, &mut node_ids);
+        if result.is_err() {
+            for node_id in &node_ids {
+                self.remove_node(*node_id);
+            }
+        }
+        self.apply_animation();
+        result.map(|()| node_ids)
+    }
+
+    fn add_scene_graph_into(
+        &mut self,
+        scene_graph: &SceneGraph,
+        node_ids: &mut Vec<NodeId>,
+    ) -> Result<(), SceneError> {
+        for (index, scene_node) in scene_graph.nodes.iter().enumerate() {
+            node_ids.push(self.add_scene_node(index, scene_node)?);
+        }
+
+        for (index, connection) in scene_graph.connections.iter().enumerate() {
+            self.add_scene_connection(node_ids, connection)
+                .map_err(|reason| SceneError::BadConnection {
+                    connection: index,
+                    reason,
+                })?;
+        }
+
+        for (index, track) in scene_graph.tracks.iter().enumerate() {
+            self.add_scene_track(node_ids, track)
+                .map_err(|reason| SceneError::BadTrack {
+                    track: index,
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Selected nodes that still exist, in the order they were selected.
+    fn selection(&self) -> Vec<NodeId> {
+        self.editor_state
+            .selected_nodes
+            .iter()
+            .copied()
+            .filter(|node_id| self.editor_state.graph.nodes.contains_key(*node_id))
+            .collect()
+    }
+
+    /// Edits removing nodes and all their connections.
+    fn remove_nodes_edits(&self, node_ids: &[NodeId]) -> Vec<Edit> {
+        let graph = &self.editor_state.graph;
+        let mut edits: Vec<Edit> = graph
+            .iter_connections()
+            .filter(|(input_id, output_id)| {
+                node_ids.contains(&graph.get_input(*input_id).node)
+                    || node_ids.contains(&graph.get_output(*output_id).node)
+            })
+            .map(|(input_id, output_id)| Edit::Disconnect {
+                output: output_param(graph, output_id),
+                input: input_param(graph, input_id),
+            })
+            .collect();
+        edits.extend(node_ids.iter().map(|node_id| Edit::RemoveNode {
+            node_id: *node_id,
+            node: self.scene_node(*node_id),
+            tracks: self.node_tracks(*node_id),
+        }));
+        edits
+    }
+
+    /// Selected nodes that can be copied. Root and group output nodes are left out, as graphs only
+    /// have one.
+    fn copyable_selection(&self) -> Vec<NodeId> {
+        self.selection()
+            .into_iter()
+            .filter(|node_id| {
+                !matches!(
+                    self.editor_state.graph[*node_id].user_data.template,
+                    NodeTemplate::Root | NodeTemplate::GroupOutput
+                )
+            })
+            .collect()
+    }
+
+    /// The selected nodes as a [`Snippet`] text, with their values, keyframes and the connections
+    /// between them.
+    pub(crate) fn copy_selection(&self) -> Option<String> {
+        let node_ids = self.copyable_selection();
+        if node_ids.is_empty() {
+            return None;
+        }
+
+        let graph = self.scene_graph_of(&node_ids);
+        let groups = self.user_state.groups.borrow().used_by(&graph);
+        Snippet::new(graph, groups).to_text().ok()
+    }
+
+    /// Copy the selected nodes, then remove them.
+    pub(crate) fn cut_selection(&mut self) -> Option<String> {
+        let text = self.copy_selection()?;
+        let mut edits = self.remove_nodes_edits(&self.copyable_selection());
+        self.apply_edits(&mut edits);
+        self.history.record(edits, false);
+        Some(text)
+    }
+
+    /// Add the nodes of a [`Snippet`] text next to where they were copied, and select them.
+    pub(crate) fn paste(&mut self, text: &str) -> Result<(), SceneError> {
+        let mut snippet = Snippet::from_text(text)?;
+        for node in &mut snippet.graph.nodes {
+            node.position[0] += PASTE_OFFSET.x;
+            node.position[1] += PASTE_OFFSET.y;
+        }
+        self.user_state
+            .groups
+            .borrow_mut()
+            .merge_missing(snippet.groups)?;
+        let node_ids = self.add_scene_graph(&snippet.graph)?;
+
+        let graph = &self.editor_state.graph;
+        let mut edits: Vec<Edit> = node_ids
+            .iter()
+            .map(|node_id| Edit::AddNode {
+                node_id: *node_id,
+                node: self.scene_node(*node_id),
+                tracks: self.node_tracks(*node_id),
+            })
+            .collect();
+        edits.extend(
+            graph
+                .iter_connections()
+                .filter(|(input_id, _)| node_ids.contains(&graph.get_input(*input_id).node))
+                .map(|(input_id, output_id)| Edit::Connect {
+                    output: output_param(graph, output_id),
+                    input: input_param(graph, input_id),
+                }),
+        );
+        self.history.record(edits, false);
+        self.editor_state.selected_nodes = node_ids;
+        Ok(())
+    }
+
+    pub(crate) fn duplicate_selection(&mut self) {
+        if let Some(text) = self.copy_selection() {
+            if let Err(err) = self.paste(&text) {
+                self.status = Some(format!("Failed to duplicate nodes: {err}"));
+            }
+        }
+    }
+
+    fn add_scene_node(
+        &mut self,
+        index: usize,
+        scene_node: &SceneNode,
+    ) -> Result<NodeId, SceneError> {
+        let template =
+            NodeTemplate::from_name(&scene_node.template, &self.user_state.groups.borrow())
+                .ok_or_else(|| SceneError::UnknownNodeType(scene_node.template.clone()))?;
+        // The inputs of a group may have changed since the node was saved, values of inputs that
+        // no longer exist are dropped.
+        let is_group = matches!(template, NodeTemplate::Group(_));
+        let node_id = self.editor_state.graph.add_node(
+            template.node_graph_label(&mut self.user_state),
+            template.user_data(&mut self.user_state),
+            |graph, node_id| template.build_node(graph, &mut self.user_state, node_id),
+        );
+        let [x, y] = scene_node.position;
+        self.editor_state
+            .node_positions
+            .insert(node_id, egui::pos2(x, y));
+        self.editor_state.node_order.push(node_id);
+
+        if template.is_variadic() {
+            let input_count = scene_node
+                .inputs
+                .iter()
+                .filter(|input| VARIADIC_INPUT_NAMES.contains(&input.name.as_str()))
+                .count()
+                .min(VARIADIC_INPUT_NAMES.len());
+            while variadic_input_count(&self.editor_state.graph, node_id) < input_count {
+                self.add_variadic_input(node_id);
+            }
+        }
+
+        let graph = &mut self.editor_state.graph;
+        for input in &scene_node.inputs {
+            let input_id = match graph[node_id].get_input(&input.name) {
+                Ok(input_id) => input_id,
+                Err(_) if is_group => continue,
+                Err(_) => {
+                    return Err(SceneError::UnknownInput {
+                        node: index,
+                        input: input.name.clone(),
+                    })
+                }
+            };
+            if let Some(value) = &input.value {
+                let value = ValueType::from(value.clone());
+                let param = &mut graph.inputs[input_id];
+                if value.data_type() != param.typ.0 && is_group {
+                    continue;
+                }
+                if value.data_type() != param.typ.0 {
+                    return Err(SceneError::ValueTypeMismatch {
+                        node: index,
+                        input: input.name.clone(),
+                    });
+                }
+                param.value = value;
+            }
+        }
+
+        Ok(node_id)
+    }
+
+    fn add_scene_connection(
+        &mut self,
+        node_ids: &[NodeId],
+        connection: &SceneConnection,
+    ) -> Result<(), String> {
+        let graph = &mut self.editor_state.graph;
+        let node_id = |index: usize| {
+            node_ids
+                .get(index)
+                .copied()
+                .ok_or_else(|| format!("there is no node {index}"))
+        };
+
+        let output_id = graph[node_id(connection.from)?]
+            .get_output(&connection.output)
+            .map_err(|_| {
+                format!(
+                    "node {} has no output {:?}",
+                    connection.from, connection.output
+                )
+            })?;
+        let to_node_id = node_id(connection.to)?;
+        // Connections to inputs a group no longer has are dropped, see `add_scene_node`.
+        let is_group = matches!(graph[to_node_id].user_data.template, NodeTemplate::Group(_));
+        let input_id = match graph[to_node_id].get_input(&connection.input) {
+            Ok(input_id) => input_id,
+            Err(_) if is_group => return Ok(()),
+            Err(_) => {
+                return Err(format!(
+                    "node {} has no input {:?}",
+                    connection.to, connection.input
+                ))
+            }
+        };
+
+        let output = graph.get_output(output_id);
+        let input = graph.get_input(input_id);
+        if output.typ.0 != input.typ.0 && is_group {
+            return Ok(());
+        }
+        if output.typ.0 != input.typ.0 {
+            return Err(format!(
+                "cannot connect {} output {:?} to {} input {:?}",
+                output.typ.0.name(),
+                connection.output,
+                input.typ.0.name(),
+                connection.input
+            ));
+        }
+        if matches!(input.kind, InputParamKind::ConstantOnly) {
+            return Err(format!("input {:?} only takes constants", connection.input));
+        }
+        if graph.connection(input_id).is_some() {
+            return Err(format!("input {:?} is already connected", connection.input));
+        }
+
+        graph.add_connection(output_id, input_id);
+        Ok(())
+    }
+
+    fn add_scene_track(&mut self, node_ids: &[NodeId], track: &SceneTrack) -> Result<(), String> {
+        let graph = &self.editor_state.graph;
+        let node_id = *node_ids
+            .get(track.node)
+            .ok_or_else(|| format!("there is no node {}", track.node))?;
+        let is_group = matches!(graph[node_id].user_data.template, NodeTemplate::Group(_));
+        let input_id = match graph[node_id].get_input(&track.input) {
+            Ok(input_id) => input_id,
+            Err(_) if is_group => return Ok(()),
+            Err(_) => {
+                return Err(format!(
+                    "node {} has no input {:?}",
+                    track.node, track.input
+                ))
+            }
+        };
+
+        let data_type = &graph.get_input(input_id).typ.0;
+        if !matches!(data_type, DataType::Scalar | DataType::Vec3) {
+            return Err(format!("input {:?} cannot be animated", track.input));
+        }
+
+        let keyframes = track
+            .keyframes
+            .iter()
+            .enumerate()
+            .map(|(index, keyframe)| {
+                let value = ValueType::from(keyframe.value.clone());
+                if value.data_type() != *data_type {
+                    return Err(format!("keyframe {index} has the wrong value type"));
+                }
+                Ok(Keyframe {
+                    time: keyframe.time,
+                    value,
+                    interpolation: keyframe.interpolation,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.user_state
+            .animation
+            .insert_track(node_id, &track.input, Track::new(keyframes));
+        Ok(())
+    }
+
+    /// Replace the selected nodes by a node of a new group containing them. Connections from other
+    /// nodes become inputs of the group, and the SDF connected to other nodes becomes its output.
+    pub(crate) fn group_selection(&mut self, name: &str) -> Result<(), String> {
+        let graph = &self.editor_state.graph;
+        let selection = self.selection();
+        if selection.is_empty() {
+            return Err("select the nodes to group first".to_string());
+        }
+        if name.is_empty() {
+            return Err("the group needs a name".to_string());
+        }
+        if NodeTemplate::from_name(name, &self.user_state.groups.borrow()).is_some() {
+            return Err(format!("there already is a node type named {name:?}"));
+        }
+        if selection.iter().any(|node_id| {
+            matches!(
+                graph[*node_id].user_data.template,
+                NodeTemplate::Root | NodeTemplate::GroupOutput
+            )
+        }) {
+            return Err("root and group output nodes cannot be grouped".to_string());
+        }
+
+        let selected = |node_id: NodeId| selection.contains(&node_id);
+        let incoming: Vec<(InputId, OutputId)> = graph
+            .iter_connections()
+            .filter(|(input_id, output_id)| {
+                selected(graph.get_input(*input_id).node)
+                    && !selected(graph.get_output(*output_id).node)
+            })
+            .collect();
+        let outgoing: Vec<(InputId, OutputId)> = graph
+            .iter_connections()
+            .filter(|(input_id, output_id)| {
+                !selected(graph.get_input(*input_id).node)
+                    && selected(graph.get_output(*output_id).node)
+            })
+            .collect();
+
+        let mut group_outputs: Vec<OutputId> =
+            outgoing.iter().map(|(_, output_id)| *output_id).collect();
+        if group_outputs.is_empty() {
+            // Nothing uses the selection yet, take the SDF that isn't used within it.
+            group_outputs = selection
+                .iter()
+                .flat_map(|node_id| graph[*node_id].output_ids())
+                .filter(|output_id| {
+                    graph.get_output(*output_id).typ.0 == DataType::CSGNode
+                        && graph
+                            .iter_connections()
+                            .all(|(_, other_output_id)| other_output_id != *output_id)
+                })
+                .collect();
+        }
+        group_outputs.sort();
+        group_outputs.dedup();
+        let [group_output] = group_outputs[..] else {
+            return Err("the selection needs exactly one SDF output".to_string());
+        };
+        if graph.get_output(group_output).typ.0 != DataType::CSGNode {
+            return Err("the output of a group must be an SDF".to_string());
+        }
+
+        // The definition: the selected nodes, with group inputs on their left and the group output
+        // on their right.
+        let mut definition = self.scene_graph_of(&selection);
+        let positions: Vec<egui::Pos2> = selection
+            .iter()
+            .map(|node_id| self.editor_state.node_positions[*node_id])
+            .collect();
+        let min = positions
+            .iter()
+            .fold(positions[0], |min, pos| min.min(*pos));
+        let max = positions
+            .iter()
+            .fold(positions[0], |max, pos| max.max(*pos));
+        let node_index = |node_id: NodeId| selection.iter().position(|id| *id == node_id).unwrap();
+
+        // Outputs of other nodes feeding the selection, with the name of the group input each
+        // becomes. An output feeding several selected inputs becomes a single group input.
+        let mut group_inputs: Vec<(OutputId, String)> = Vec::new();
+        for (input_id, output_id) in &incoming {
+            let input = graph.get_input(*input_id);
+            let group_input_index = match group_inputs
+                .iter()
+                .position(|(other, _)| other == output_id)
+            {
+                Some(index) => index,
+                None => {
+                    let base_name = input_name(graph, *input_id);
+                    let mut input_name = base_name.to_string();
+                    for i in 2.. {
+                        if group_inputs.iter().all(|(_, other)| *other != input_name) {
+                            break;
+                        }
+                        input_name = format!("{base_name} {i}");
+                    }
+
+                    let mut inputs = vec![SceneInput {
+                        name: "name".to_string(),
+                        value: Some(SceneValue::Text(input_name.clone())),
+                    }];
+                    if input.typ.0.has_constant() {
+                        inputs.push(SceneInput {
+                            name: "default".to_string(),
+                            value: input.value.to_scene_value(),
+                        });
+                    }
+                    definition.nodes.push(SceneNode {
+                        template: NodeTemplate::GroupInput(input.typ.0).name().to_string(),
+                        position: [min.x - 250.0, min.y + 150.0 * group_inputs.len() as f32],
+                        inputs,
+                    });
+                    group_inputs.push((*output_id, input_name));
+                    group_inputs.len() - 1
+                }
+            };
+            definition.connections.push(SceneConnection {
+                from: selection.len() + group_input_index,
+                output: "value".to_string(),
+                to: node_index(input.node),
+                input: input_name(graph, *input_id).to_string(),
+            });
+        }
+
+        definition.nodes.push(SceneNode {
+            template: NodeTemplate::GroupOutput.name().to_string(),
+            position: [max.x + 250.0, min.y],
+            inputs: vec![SceneInput {
+                name: "SDF".to_string(),
+                value: None,
+            }],
+        });
+        definition.connections.push(SceneConnection {
+            from: node_index(graph.get_output(group_output).node),
+            output: output_name(graph, group_output).to_string(),
+            to: definition.nodes.len() - 1,
+            input: "SDF".to_string(),
+        });
+
+        // Replace the selection by a node of the group, as one undoable step.
+        let mut edits = self.remove_nodes_edits(&selection);
+        let group_input_params: Vec<(Param, String)> = group_inputs
+            .iter()
+            .map(|(output_id, input_name)| (output_param(graph, *output_id), input_name.clone()))
+            .collect();
+        let outgoing_params: Vec<Param> = outgoing
+            .iter()
+            .map(|(input_id, _)| input_param(graph, *input_id))
+            .collect();
+
+        self.user_state
+            .groups
+            .borrow_mut()
+            .set_definition(name, definition)?;
+        self.apply_edits(&mut edits);
+
+        let center = min + (max - min) / 2.0;
+        let group_node = SceneNode {
+            template: name.to_string(),
+            position: [center.x, center.y],
+            inputs: Vec::new(),
+        };
+        let group_node_id = self
+            .add_scene_node(0, &group_node)
+            .map_err(|err| err.to_string())?;
+        let mut connect_edits: Vec<Edit> = group_input_params
+            .into_iter()
+            .map(|(output, input_name)| Edit::Connect {
+                output,
+                input: Param {
+                    node_id: group_node_id,
+                    name: input_name,
+                },
+            })
+            .chain(outgoing_params.into_iter().map(|input| Edit::Connect {
+                output: Param {
+                    node_id: group_node_id,
+                    name: "SDF".to_string(),
+                },
+                input,
+            }))
+            .collect();
+        self.apply_edits(&mut connect_edits);
+
+        edits.push(Edit::AddNode {
+            node_id: group_node_id,
+            node: self.scene_node(group_node_id),
+            tracks: Vec::new(),
+        });
+        edits.extend(connect_edits);
+        self.history.record(edits, false);
+        Ok(())
+    }
+
+    /// Update the inputs of the nodes of a group after its definition changed, keeping the values
+    /// and connections of inputs that still exist.
+    pub(crate) fn update_group_instances(&mut self, name: &str) {
+        let interface = self.user_state.groups.borrow().interface(name);
+        let graph = &mut self.editor_state.graph;
+        let group_node_ids: Vec<NodeId> = graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| matches!(&node.user_data.template, NodeTemplate::Group(group) if group == name))
+            .map(|(node_id, _)| node_id)
+            .collect();
+
+        for node_id in group_node_ids {
+            for (input_name, input_id) in graph[node_id].inputs.clone() {
+                let data_type = graph.get_input(input_id).typ.0;
+                if !interface
+                    .iter()
+                    .any(|input| input.name == input_name && input.data_type == data_type)
+                {
+                    graph.remove_input_param(input_id);
+                    self.user_state.animation.remove_track(node_id, &input_name);
+                }
+            }
+            for input in &interface {
+                if graph[node_id].get_input(&input.name).is_err() {
+                    add_group_input(graph, node_id, input);
+                }
+            }
+            graph[node_id].inputs.sort_by_key(|(input_name, _)| {
+                interface.iter().position(|input| input.name == *input_name)
+            });
+        }
+    }
+
+    /// Evaluate the SDF connected to the root node, or to the group output node when editing a
+    /// group, with `time` in seconds for the `Time` node and keyframed inputs. `has_grid` is
+    /// whether a mesh was baked for `GridSdf` nodes. Problems found on the way are shown on the
+    /// nodes until the next evaluation.
+    pub fn evaluate_root(&mut self, time: f32, has_grid: bool) -> Option<CSGNode> {
+        let graph = &self.editor_state.graph;
+        let has_root = graph
             .nodes
             .iter()
-            .find(|(_, node)| matches!(node.user_data.template, NodeTemplate::Root))?;
-        let input_id = root_node.get_input("SDF").unwrap();
-        let mut evaluator = Evaluator::new(&self.editor_state.graph);
-        evaluator.evaluate_input(input_id).to_csg_node()
+            .any(|(_, node)| matches!(node.user_data.template, NodeTemplate::Root));
+        let is_output = |template: &NodeTemplate| {
+            if has_root {
+                matches!(template, NodeTemplate::Root)
+            } else {
+                matches!(template, NodeTemplate::GroupOutput)
+            }
+        };
+
+        let mut evaluator = Evaluator::new(
+            graph,
+            &self.user_state.animation,
+            time,
+            &self.user_state.groups,
+            has_grid,
+            HashMap::new(),
+            Vec::new(),
+        );
+        let csg_node = evaluator
+            .evaluate_sdf(is_output)
+            .and_then(|(node_id, csg_node)| {
+                let mut builder = CSGCommandBufferBuilder::new();
+                csg_node.build_commands(&mut builder);
+                if builder.max_value_stack_size > VALUE_STACK_MAX_SIZE
+                    || builder.max_pos_stack_size > POS_STACK_MAX_SIZE
+                {
+                    let diagnostic = Diagnostic::StackTooDeep {
+                        value_stack_size: builder.max_value_stack_size,
+                        pos_stack_size: builder.max_pos_stack_size,
+                    };
+                    evaluator.diagnostics.push((node_id, diagnostic));
+                    return None;
+                }
+                if builder.text_atlas_height() > TEXT_ATLAS_MAX_SIZE {
+                    evaluator
+                        .diagnostics
+                        .push((node_id, Diagnostic::TooMuchText));
+                    return None;
+                }
+                Some(csg_node)
+            });
+
+        let mut diagnostics = evaluator.diagnostics;
+        diagnostics.dedup();
+        self.user_state.diagnostics = diagnostics;
+        csg_node
+    }
+
+    /// Problems found by the last evaluation, with the label of the node each was found on.
+    pub(crate) fn diagnostics(&self) -> Vec<(String, &Diagnostic)> {
+        let graph = &self.editor_state.graph;
+        self.user_state
+            .diagnostics
+            .iter()
+            .filter(|(node_id, _)| graph.nodes.contains_key(*node_id))
+            .map(|(node_id, diagnostic)| (graph[*node_id].label.clone(), diagnostic))
+            .collect()
     }
 }
 
 struct Evaluator<'a> {
     graph: &'a MyGraph,
+    animation: &'a Animation,
+    /// Values of evaluated outputs, `None` for outputs of nodes that failed.
     output_cache: HashMap<OutputId, Option<ValueType>>,
+    time: f32,
+    groups: &'a SharedGroupLibrary,
+    has_grid: bool,
+    /// Values of the inputs of the group node whose definition is evaluated, by name.
+    group_inputs: HashMap<String, ValueType>,
+    /// Names of the groups whose definitions are being evaluated, outermost first. Groups
+    /// containing themselves would be evaluated forever, so they fail with
+    /// [`Diagnostic::RecursiveGroup`] instead.
+    group_chain: Vec<String>,
+    /// Nodes whose inputs are being evaluated, to detect cycles.
+    in_progress: HashSet<NodeId>,
+    diagnostics: Vec<(NodeId, Diagnostic)>,
 }
 
 impl<'a> Evaluator<'a> {
-    fn new(graph: &'a MyGraph) -> Self {
+    fn new(
+        graph: &'a MyGraph,
+        animation: &'a Animation,
+        time: f32,
+        groups: &'a SharedGroupLibrary,
+        has_grid: bool,
+        group_inputs: HashMap<String, ValueType>,
+        group_chain: Vec<String>,
+    ) -> Self {
         Self {
             graph,
+            animation,
             output_cache: HashMap::new(),
+            time,
+            groups,
+            has_grid,
+            group_inputs,
+            group_chain,
+            in_progress: HashSet::new(),
+            diagnostics: Vec::new(),
         }
     }
 
-    fn evaluate_input(&mut self, input_id: InputId) -> ValueType {
-        self.graph
-            .connection(input_id)
-            .and_then(|output_id| self.evaluate_output(output_id))
-            .unwrap_or_else(|| self.graph.get_input(input_id).value.clone())
+    /// Evaluate the SDF connected to the node whose template matches `is_output`, and return it
+    /// with the id of that node.
+    fn evaluate_sdf(
+        &mut self,
+        is_output: impl Fn(&NodeTemplate) -> bool,
+    ) -> Option<(NodeId, CSGNode)> {
+        let graph = self.graph;
+        let output_node_ids: Vec<NodeId> = graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| is_output(&node.user_data.template))
+            .map(|(node_id, _)| node_id)
+            .collect();
+        let [node_id] = output_node_ids[..] else {
+            for node_id in output_node_ids {
+                self.diagnostics
+                    .push((node_id, Diagnostic::MultipleOutputs));
+            }
+            return None;
+        };
+
+        let input_params = self.evaluate_inputs(node_id)?;
+        match input_params.csg_node("SDF") {
+            Ok(csg_node) => Some((node_id, csg_node)),
+            Err(diagnostic) => {
+                self.diagnostics.push((node_id, diagnostic));
+                None
+            }
+        }
+    }
+
+    /// Value of an input, `None` if it is connected to a node that failed.
+    fn evaluate_input(&mut self, input_id: InputId) -> Option<ValueType> {
+        match self.graph.connection(input_id) {
+            Some(output_id) => self.evaluate_output(output_id),
+            None => Some(self.evaluate_constant(input_id)),
+        }
+    }
+
+    /// Value of an unconnected input, sampled from its keyframes if it's animated.
+    fn evaluate_constant(&self, input_id: InputId) -> ValueType {
+        let input = self.graph.get_input(input_id);
+        self.animation
+            .sample(input.node, input_name(self.graph, input_id), self.time)
+            .unwrap_or_else(|| input.value.clone())
     }
 
     fn evaluate_output(&mut self, output_id: OutputId) -> Option<ValueType> {
+        let node_id = self.graph.get_output(output_id).node;
         if !self.output_cache.contains_key(&output_id) {
-            self.evaluate_node(self.graph.get_output(output_id).node);
+            if self.in_progress.contains(&node_id) {
+                self.diagnostics.push((node_id, Diagnostic::Cycle));
+                return None;
+            }
+            self.evaluate_node(node_id);
         }
         self.output_cache.get(&output_id).cloned().flatten()
     }
 
     fn evaluate_node(&mut self, node_id: NodeId) {
-        let node = &self.graph[node_id];
+        self.in_progress.insert(node_id);
+        // Nodes with failed inputs fail without a diagnostic of their own, the diagnostic is on
+        // the node where the failure started.
+        let values = match self.evaluate_inputs(node_id) {
+            Some(input_params) => match self.evaluate_template(node_id, input_params) {
+                Ok(values) => values,
+                Err(diagnostic) => {
+                    self.diagnostics.push((node_id, diagnostic));
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        self.in_progress.remove(&node_id);
 
-        match &node.user_data.template {
-            NodeTemplate::Root => {}
+        for (i, (_, output_id)) in self.graph[node_id].outputs.iter().enumerate() {
+            self.output_cache.insert(*output_id, values.get(i).cloned());
+        }
+    }
+
+    /// Values of the outputs of a node, in order.
+    fn evaluate_template(
+        &mut self,
+        node_id: NodeId,
+        input_params: InputParams,
+    ) -> Result<Vec<ValueType>, Diagnostic> {
+        match &self.graph[node_id].user_data.template {
+            NodeTemplate::Root | NodeTemplate::GroupOutput => Ok(Vec::new()),
             NodeTemplate::CSGNode(template) => {
-                let input_params = node
-                    .inputs
-                    .iter()
-                    .map(|(name, input_id)| (name.clone(), self.evaluate_input(*input_id)))
-                    .collect();
-                self.output_cache.insert(
-                    node.outputs[0].1,
-                    template.evaluate(input_params).map(ValueType::csg_node),
+                // Without a grid the node is empty, but the rest of the scene still renders.
+                if matches!(template, CSGNodeTemplate::GridSdf(_)) && !self.has_grid {
+                    self.diagnostics.push((node_id, Diagnostic::NoGrid));
+                }
+                Ok(vec![ValueType::csg_node(template.evaluate(&input_params)?)])
+            }
+            NodeTemplate::SDF2DNode(template) => {
+                Ok(vec![ValueType::sdf_2d(template.evaluate(&input_params)?)])
+            }
+            NodeTemplate::Math(template) => template.evaluate(&input_params, self.time),
+            NodeTemplate::Group(name) => {
+                if self.group_chain.contains(name) {
+                    return Err(Diagnostic::RecursiveGroup);
+                }
+                let group = GroupLibrary::compiled(self.groups, name)
+                    .ok_or_else(|| Diagnostic::BrokenGroup(name.clone()))?;
+                // Problems within the definition are shown when editing it.
+                let mut evaluator = Evaluator::new(
+                    &group.editor_state.graph,
+                    &group.user_state.animation,
+                    self.time,
+                    self.groups,
+                    self.has_grid,
+                    input_params.into_values(),
+                    [self.group_chain.as_slice(), std::slice::from_ref(name)].concat(),
                 );
+                let (_, csg_node) = evaluator
+                    .evaluate_sdf(|template| matches!(template, NodeTemplate::GroupOutput))
+                    .ok_or_else(|| Diagnostic::BrokenGroup(name.clone()))?;
+                Ok(vec![ValueType::csg_node(csg_node)])
+            }
+            NodeTemplate::GroupInput(data_type) => {
+                let name = input_params.text("name")?;
+                let value = match self.group_inputs.get(&name) {
+                    Some(value) if value.data_type() != *data_type => {
+                        return Err(Diagnostic::TypeMismatch {
+                            input: name,
+                            expected: *data_type,
+                            found: value.data_type(),
+                        });
+                    }
+                    Some(value) => value.clone(),
+                    None if data_type.has_constant() => input_params
+                        .into_values()
+                        .remove("default")
+                        .ok_or_else(|| Diagnostic::MissingInput("default".to_string()))?,
+                    None => data_type.default_value(),
+                };
+                Ok(vec![value])
             }
         }
     }
+
+    /// Values of the inputs of a node, `None` if one of them is connected to a node that failed.
+    fn evaluate_inputs(&mut self, node_id: NodeId) -> Option<InputParams> {
+        let mut values = HashMap::new();
+        for (name, input_id) in &self.graph[node_id].inputs {
+            values.insert(name.clone(), self.evaluate_input(*input_id)?);
+        }
+        Some(InputParams::new(values))
+    }
+}
+
+/// Keyframes of a track as dots on a line, with the current time as a vertical line.
+fn draw_track_strip(ui: &mut egui::Ui, track: &Track, time: f32, duration: f32) {
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 12.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+    let x = |time: f32| rect.left() + rect.width() * (time / duration).clamp(0.0, 1.0);
+
+    painter.hline(rect.x_range(), rect.center().y, stroke);
+    for keyframe in track.keyframes() {
+        painter.circle_filled(
+            egui::pos2(x(keyframe.time), rect.center().y),
+            4.0,
+            ui.visuals().selection.bg_fill,
+        );
+    }
+    painter.vline(x(time), rect.y_range(), ui.visuals().selection.stroke);
 }
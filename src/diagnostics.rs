@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use egui_node_graph::DataTypeTrait;
+
+use crate::csg_node_graph::{DataType, ValueType};
+use crate::ray_marching::csg::builder::{
+    POS_STACK_MAX_SIZE, TEXT_ATLAS_MAX_SIZE, VALUE_STACK_MAX_SIZE,
+};
+use crate::ray_marching::csg::{CSGNode, SDF2DNode};
+
+/// Problem that keeps a node from being evaluated, shown on the node and in the status bar.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Diagnostic {
+    /// An input that must be connected isn't.
+    MissingInput(String),
+    TypeMismatch {
+        input: String,
+        expected: DataType,
+        found: DataType,
+    },
+    InvalidInput {
+        input: String,
+        reason: &'static str,
+    },
+    /// The node depends on its own output.
+    Cycle,
+    /// There are several root or group output nodes, so it's unclear which one to render.
+    MultipleOutputs,
+    /// The scene needs larger stacks than the shader has.
+    StackTooDeep {
+        value_stack_size: usize,
+        pos_stack_size: usize,
+    },
+    /// The definition of the node's group is missing or doesn't evaluate to an SDF.
+    BrokenGroup(String),
+    /// The group contains itself, directly or through other groups.
+    RecursiveGroup,
+    /// The text of the node is still being rasterized, in the background.
+    Rasterizing,
+    /// The texts of the scene don't fit into the text atlas texture together.
+    TooMuchText,
+    /// No mesh was baked into the grid the node samples, e.g. after opening a scene, as grids
+    /// aren't saved with scenes.
+    NoGrid,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::MissingInput(input) => write!(f, "input {input:?} is not connected"),
+            Diagnostic::TypeMismatch {
+                input,
+                expected,
+                found,
+            } => write!(
+                f,
+                "input {input:?} expects a {} but got a {}",
+                expected.name(),
+                found.name()
+            ),
+            Diagnostic::InvalidInput { input, reason } => write!(f, "input {input:?} {reason}"),
+            Diagnostic::Cycle => write!(f, "the node depends on its own output"),
+            Diagnostic::MultipleOutputs => write!(f, "only one output node can be rendered"),
+            Diagnostic::StackTooDeep {
+                value_stack_size,
+                pos_stack_size,
+            } => write!(
+                f,
+                "the scene is nested too deeply for the shader, it needs {value_stack_size} of \
+                 {VALUE_STACK_MAX_SIZE} distances and {pos_stack_size} of {POS_STACK_MAX_SIZE} \
+                 positions on the stack"
+            ),
+            Diagnostic::BrokenGroup(name) => {
+                write!(
+                    f,
+                    "group {name:?} is missing or has errors, edit it to see them"
+                )
+            }
+            Diagnostic::RecursiveGroup => write!(f, "the group contains itself"),
+            Diagnostic::Rasterizing => write!(f, "the text is still being rasterized"),
+            Diagnostic::TooMuchText => write!(
+                f,
+                "the texts of the scene don't fit into one {TEXT_ATLAS_MAX_SIZE} pixel texture"
+            ),
+            Diagnostic::NoGrid => write!(f, "no mesh is baked, bake one to render this node"),
+        }
+    }
+}
+
+/// Evaluated inputs of a node by name, read with the type the node expects.
+pub(crate) struct InputParams(HashMap<String, ValueType>);
+
+impl InputParams {
+    pub(crate) fn new(values: HashMap<String, ValueType>) -> Self {
+        Self(values)
+    }
+
+    pub(crate) fn into_values(self) -> HashMap<String, ValueType> {
+        self.0
+    }
+
+    /// Whether the node has the input, for variadic inputs which may not have been added.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    fn get(&self, name: &str, expected: DataType) -> Result<&ValueType, Diagnostic> {
+        let value = self
+            .0
+            .get(name)
+            .ok_or_else(|| Diagnostic::MissingInput(name.to_string()))?;
+        let found = value.data_type();
+        if found != expected {
+            return Err(Diagnostic::TypeMismatch {
+                input: name.to_string(),
+                expected,
+                found,
+            });
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn scalar(&self, name: &str) -> Result<f32, Diagnostic> {
+        Ok(self.get(name, DataType::Scalar)?.to_scalar().unwrap())
+    }
+
+    pub(crate) fn vec3(&self, name: &str) -> Result<[f32; 3], Diagnostic> {
+        Ok(self.get(name, DataType::Vec3)?.to_vec3().unwrap())
+    }
+
+    pub(crate) fn text(&self, name: &str) -> Result<String, Diagnostic> {
+        Ok(self.get(name, DataType::Text)?.to_text().unwrap())
+    }
+
+    /// SDF input, `None` if it is not connected.
+    pub(crate) fn optional_csg_node(&self, name: &str) -> Result<Option<CSGNode>, Diagnostic> {
+        Ok(self.get(name, DataType::CSGNode)?.to_csg_node())
+    }
+
+    pub(crate) fn csg_node(&self, name: &str) -> Result<CSGNode, Diagnostic> {
+        self.optional_csg_node(name)?
+            .ok_or_else(|| Diagnostic::MissingInput(name.to_string()))
+    }
+
+    pub(crate) fn sdf_2d(&self, name: &str) -> Result<SDF2DNode, Diagnostic> {
+        self.get(name, DataType::SDF2D)?
+            .to_sdf_2d()
+            .ok_or_else(|| Diagnostic::MissingInput(name.to_string()))
+    }
+}
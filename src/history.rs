@@ -0,0 +1,262 @@
+use eframe::egui;
+use egui_node_graph::NodeId;
+
+use crate::animation::Track;
+use crate::scene::{SceneNode, SceneValue};
+
+/// Number of steps kept for undoing, older steps are forgotten.
+const MAX_UNDO_STEPS: usize = 256;
+
+/// Input or output of a node by name. Unlike input and output ids, names stay valid when a deleted
+/// node is restored under a new id.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Param {
+    pub(crate) node_id: NodeId,
+    pub(crate) name: String,
+}
+
+/// Reversible change to a node graph.
+#[derive(Debug, Clone)]
+pub(crate) enum Edit {
+    AddNode {
+        node_id: NodeId,
+        node: SceneNode,
+        /// Keyframe tracks of the inputs of the node, by input name.
+        tracks: Vec<(String, Track)>,
+    },
+    RemoveNode {
+        node_id: NodeId,
+        node: SceneNode,
+        tracks: Vec<(String, Track)>,
+    },
+    Connect {
+        output: Param,
+        input: Param,
+    },
+    Disconnect {
+        output: Param,
+        input: Param,
+    },
+    SetValue {
+        input: Param,
+        old: SceneValue,
+        new: SceneValue,
+    },
+    MoveNode {
+        node_id: NodeId,
+        delta: egui::Vec2,
+    },
+    /// Add a variadic input after the others, with the given constant value.
+    AddInput {
+        node_id: NodeId,
+        value: Option<SceneValue>,
+    },
+    /// Remove the last variadic input, which has the given constant value.
+    RemoveInput {
+        node_id: NodeId,
+        value: Option<SceneValue>,
+    },
+    /// Replace the keyframes of an input, `None` for no keyframes.
+    SetTrack {
+        input: Param,
+        old: Option<Track>,
+        new: Option<Track>,
+    },
+}
+
+impl Edit {
+    /// The edit undoing this one.
+    pub(crate) fn inverse(self) -> Edit {
+        match self {
+            Edit::AddNode {
+                node_id,
+                node,
+                tracks,
+            } => Edit::RemoveNode {
+                node_id,
+                node,
+                tracks,
+            },
+            Edit::RemoveNode {
+                node_id,
+                node,
+                tracks,
+            } => Edit::AddNode {
+                node_id,
+                node,
+                tracks,
+            },
+            Edit::Connect { output, input } => Edit::Disconnect { output, input },
+            Edit::Disconnect { output, input } => Edit::Connect { output, input },
+            Edit::SetValue { input, old, new } => Edit::SetValue {
+                input,
+                old: new,
+                new: old,
+            },
+            Edit::MoveNode { node_id, delta } => Edit::MoveNode {
+                node_id,
+                delta: -delta,
+            },
+            Edit::AddInput { node_id, value } => Edit::RemoveInput { node_id, value },
+            Edit::RemoveInput { node_id, value } => Edit::AddInput { node_id, value },
+            Edit::SetTrack { input, old, new } => Edit::SetTrack {
+                input,
+                old: new,
+                new: old,
+            },
+        }
+    }
+
+    /// Refer to the node `new` wherever this edit refers to the node `old`.
+    fn remap(&mut self, old: NodeId, new: NodeId) {
+        let remap = |node_id: &mut NodeId| {
+            if *node_id == old {
+                *node_id = new;
+            }
+        };
+        match self {
+            Edit::AddNode { node_id, .. }
+            | Edit::RemoveNode { node_id, .. }
+            | Edit::MoveNode { node_id, .. }
+            | Edit::AddInput { node_id, .. }
+            | Edit::RemoveInput { node_id, .. } => remap(node_id),
+            Edit::Connect { output, input } | Edit::Disconnect { output, input } => {
+                remap(&mut output.node_id);
+                remap(&mut input.node_id);
+            }
+            Edit::SetValue { input, .. } | Edit::SetTrack { input, .. } => {
+                remap(&mut input.node_id)
+            }
+        }
+    }
+
+    /// Whether `next` continues this edit, like consecutive frames of dragging a value.
+    fn can_merge(&self, next: &Edit) -> bool {
+        match (self, next) {
+            (
+                Edit::SetValue { input, .. },
+                Edit::SetValue {
+                    input: next_input, ..
+                },
+            )
+            | (
+                Edit::SetTrack { input, .. },
+                Edit::SetTrack {
+                    input: next_input, ..
+                },
+            ) => input == next_input,
+            (
+                Edit::MoveNode { node_id, .. },
+                Edit::MoveNode {
+                    node_id: next_node_id,
+                    ..
+                },
+            ) => node_id == next_node_id,
+            _ => false,
+        }
+    }
+
+    fn merge(&mut self, next: Edit) {
+        match (self, next) {
+            (Edit::SetValue { new, .. }, Edit::SetValue { new: next_new, .. }) => *new = next_new,
+            (Edit::SetTrack { new, .. }, Edit::SetTrack { new: next_new, .. }) => *new = next_new,
+            (
+                Edit::MoveNode { delta, .. },
+                Edit::MoveNode {
+                    delta: next_delta, ..
+                },
+            ) => *delta += next_delta,
+            _ => unreachable!("only edits passing `can_merge` are merged"),
+        }
+    }
+}
+
+/// Undo and redo stacks of a node graph. Each step is the list of edits made in one frame.
+#[derive(Default)]
+pub(crate) struct History {
+    undo_stack: Vec<Vec<Edit>>,
+    redo_stack: Vec<Vec<Edit>>,
+    /// Whether the interaction that made the last step is still going on, e.g. a value is still
+    /// being dragged, so that the edits of the next frame are merged into it.
+    merging: bool,
+}
+
+impl History {
+    /// Record the edits made in a frame, `ongoing` is whether the interaction making them continues
+    /// in the next frame.
+    pub(crate) fn record(&mut self, edits: Vec<Edit>, ongoing: bool) {
+        if edits.is_empty() {
+            self.merging &= ongoing;
+            return;
+        }
+
+        let merged = self.merging
+            && self.undo_stack.last_mut().is_some_and(|last_step| {
+                let can_merge = last_step.len() == edits.len()
+                    && last_step
+                        .iter()
+                        .zip(&edits)
+                        .all(|(edit, next)| edit.can_merge(next));
+                if can_merge {
+                    for (edit, next) in last_step.iter_mut().zip(edits.clone()) {
+                        edit.merge(next);
+                    }
+                }
+                can_merge
+            });
+        if !merged {
+            self.push_undo(edits);
+            self.redo_stack.clear();
+        }
+        self.merging = ongoing;
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub(crate) fn pop_undo(&mut self) -> Option<Vec<Edit>> {
+        self.merging = false;
+        self.undo_stack.pop()
+    }
+
+    pub(crate) fn pop_redo(&mut self) -> Option<Vec<Edit>> {
+        self.merging = false;
+        self.redo_stack.pop()
+    }
+
+    pub(crate) fn push_undo(&mut self, step: Vec<Edit>) {
+        if self.undo_stack.len() == MAX_UNDO_STEPS {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(step);
+    }
+
+    pub(crate) fn push_redo(&mut self, step: Vec<Edit>) {
+        self.redo_stack.push(step);
+    }
+
+    /// Refer to the node `new` wherever a step refers to the node `old`, after `old` was restored
+    /// as `new`.
+    pub(crate) fn remap(&mut self, old: NodeId, new: NodeId) {
+        for edit in self
+            .undo_stack
+            .iter_mut()
+            .chain(&mut self.redo_stack)
+            .flatten()
+        {
+            edit.remap(old, new);
+        }
+    }
+}
+
+/// Refer to the node `new` wherever one of `edits` refers to the node `old`.
+pub(crate) fn remap_edits(edits: &mut [Edit], old: NodeId, new: NodeId) {
+    for edit in edits {
+        edit.remap(old, new);
+    }
+}
@@ -1,10 +1,25 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use eframe::{egui, egui_wgpu};
 
+use crate::csg_node_graph::CSGNodeGraph;
+use crate::node_groups::SharedGroupLibrary;
+use crate::ray_marching::csg::TextGrid;
 use crate::ray_marching::renderer::{RayMarchingCallback, RayMarchingResources};
+use crate::scene::{Scene, SceneGraph};
 
+mod animation;
 mod camera;
+mod clock;
 mod csg_node_graph;
+mod diagnostics;
+mod history;
+mod math_nodes;
+mod mesh_import;
+mod node_groups;
 mod ray_marching;
+mod scene;
 
 fn main() {
     let native_options = eframe::NativeOptions {
@@ -20,8 +35,29 @@ fn main() {
 }
 
 struct RayMarchingApp {
-    csg_node_graph: csg_node_graph::CSGNodeGraph,
+    /// The graph shown in the editor, the definition of a group while editing one.
+    csg_node_graph: CSGNodeGraph,
+    groups: SharedGroupLibrary,
+    /// Groups being edited, innermost last, with the graph each was opened from.
+    group_edit_stack: Vec<(String, CSGNodeGraph)>,
+    mesh_import: mesh_import::MeshImport,
+    clock: clock::Clock,
     camera_controller: camera::OrbitCameraController,
+    /// File the scene was last opened from or saved to.
+    scene_path: Option<PathBuf>,
+    /// Text entered in the window of a menu entry, e.g. a path as there is no native file dialog.
+    prompt: Option<(Prompt, String)>,
+    /// Result of the last file or group operation.
+    status: String,
+}
+
+#[derive(Copy, Clone)]
+enum Prompt {
+    Open,
+    SaveAs,
+    OpenLibrary,
+    SaveLibrary,
+    GroupName,
 }
 
 impl RayMarchingApp {
@@ -33,20 +69,362 @@ impl RayMarchingApp {
             .callback_resources
             .insert(RayMarchingResources::new(wgpu_render_state));
 
+        let groups = SharedGroupLibrary::default();
         Self {
-            csg_node_graph: csg_node_graph::CSGNodeGraph::default(),
+            csg_node_graph: CSGNodeGraph::new(groups.clone()),
+            groups,
+            group_edit_stack: Vec::new(),
+            mesh_import: mesh_import::MeshImport::default(),
+            clock: clock::Clock::default(),
             camera_controller: camera::OrbitCameraController::new([0.0, 0.0, 0.0], 5.0),
+            scene_path: None,
+            prompt: None,
+            status: String::new(),
+        }
+    }
+
+    fn open_scene(&mut self, path: PathBuf) {
+        let result = Scene::load(&path).and_then(|scene| {
+            // Groups used by the scene must be known before its graph is built.
+            self.groups.borrow_mut().merge(scene.groups.clone())?;
+            let node_graph = CSGNodeGraph::from_scene_graph(&scene.graph, self.groups.clone())?;
+            Ok((scene, node_graph))
+        });
+        match result {
+            Ok((scene, node_graph)) => {
+                self.csg_node_graph = node_graph;
+                self.group_edit_stack.clear();
+                self.camera_controller.set_pose(&scene.camera);
+                self.status = format!("Opened {}", path.display());
+                self.scene_path = Some(path);
+            }
+            Err(err) => self.status = format!("Failed to open {}: {err}", path.display()),
+        }
+    }
+
+    fn save_scene(&mut self, path: PathBuf) {
+        let scene_graph = match self.group_edit_stack.first() {
+            Some((_, node_graph)) => node_graph.to_scene_graph(),
+            None => self.csg_node_graph.to_scene_graph(),
+        };
+        let scene = Scene::new(
+            self.camera_controller.pose(),
+            scene_graph,
+            self.group_definitions(),
+        );
+        match scene.save(&path) {
+            Ok(()) => {
+                self.status = format!("Saved {}", path.display());
+                self.scene_path = Some(path);
+            }
+            Err(err) => self.status = format!("Failed to save {}: {err}", path.display()),
+        }
+    }
+
+    /// Definitions of the groups in the library, including unfinished edits of the groups being
+    /// edited.
+    fn group_definitions(&self) -> BTreeMap<String, SceneGraph> {
+        let mut definitions = self.groups.borrow().definitions().clone();
+        let edited_graphs = self
+            .group_edit_stack
+            .iter()
+            .skip(1)
+            .map(|(_, node_graph)| node_graph)
+            .chain([&self.csg_node_graph]);
+        for ((name, _), node_graph) in self.group_edit_stack.iter().zip(edited_graphs) {
+            definitions.insert(name.clone(), node_graph.to_scene_graph());
+        }
+        definitions
+    }
+
+    fn open_group_library(&mut self, path: PathBuf) {
+        let result = self.groups.borrow_mut().load(&path);
+        match result {
+            Ok(()) => {
+                let names: Vec<String> = self.groups.borrow().names().cloned().collect();
+                for name in names {
+                    self.update_group_instances(&name);
+                }
+                self.status = format!("Opened group library {}", path.display());
+            }
+            Err(err) => self.status = format!("Failed to open {}: {err}", path.display()),
+        }
+    }
+
+    fn save_group_library(&mut self, path: PathBuf) {
+        // Unfinished edits are saved too, like in scenes.
+        let mut library = node_groups::GroupLibrary::default();
+        let result = library
+            .merge(self.group_definitions())
+            .and_then(|()| library.save(&path));
+        match result {
+            Ok(()) => self.status = format!("Saved group library {}", path.display()),
+            Err(err) => self.status = format!("Failed to save {}: {err}", path.display()),
+        }
+    }
+
+    fn group_selection(&mut self, name: &str) {
+        self.status = match self.csg_node_graph.group_selection(name) {
+            Ok(()) => format!("Grouped the selection into {name:?}"),
+            Err(err) => format!("Failed to group the selection: {err}"),
+        };
+    }
+
+    /// Show the definition of a group in the editor instead of the current graph.
+    fn edit_group(&mut self, name: String) {
+        if self
+            .group_edit_stack
+            .iter()
+            .any(|(other, _)| *other == name)
+        {
+            self.status = format!("Group {name:?} is already being edited");
+            return;
+        }
+        let Some(definition) = self.groups.borrow().definition(&name).cloned() else {
+            return;
+        };
+        match CSGNodeGraph::from_scene_graph(&definition, self.groups.clone()) {
+            Ok(node_graph) => {
+                let parent = std::mem::replace(&mut self.csg_node_graph, node_graph);
+                self.group_edit_stack.push((name, parent));
+            }
+            Err(err) => self.status = format!("Failed to edit group {name:?}: {err}"),
+        }
+    }
+
+    /// Store the edited definition of the innermost group being edited, and go back to the graph
+    /// it was opened from.
+    fn finish_group_edit(&mut self) {
+        let Some((name, _)) = self.group_edit_stack.last() else {
+            return;
+        };
+        let definition = self.csg_node_graph.to_scene_graph();
+        if let Err(err) = self.groups.borrow_mut().set_definition(name, definition) {
+            // Keep editing, so that the group can be fixed.
+            self.status = format!("Failed to store group {name:?}: {err}");
+            return;
+        }
+        let Some((name, parent)) = self.group_edit_stack.pop() else {
+            return;
+        };
+        self.csg_node_graph = parent;
+        self.update_group_instances(&name);
+    }
+
+    /// Update the nodes of a group in all open graphs after its definition changed.
+    fn update_group_instances(&mut self, name: &str) {
+        self.csg_node_graph.update_group_instances(name);
+        for (_, node_graph) in &mut self.group_edit_stack {
+            node_graph.update_group_instances(name);
+        }
+    }
+
+    fn show_prompt(&mut self, prompt: Prompt) {
+        let text = match prompt {
+            Prompt::Open | Prompt::SaveAs => self
+                .scene_path
+                .as_ref()
+                .map_or("scene.ron".to_string(), |path| path.display().to_string()),
+            Prompt::OpenLibrary | Prompt::SaveLibrary => "groups.ron".to_string(),
+            Prompt::GroupName => String::new(),
+        };
+        self.prompt = Some((prompt, text));
+    }
+
+    /// Result of the last operation, and the problems found by the last evaluation of the graph.
+    fn draw_status_bar(&self, ctx: &egui::Context) {
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let diagnostics = self.csg_node_graph.diagnostics();
+                if let Some((label, diagnostic)) = diagnostics.first() {
+                    let text = match diagnostics.len() {
+                        1 => format!("⚠ {label}: {diagnostic}"),
+                        count => format!("⚠ {label}: {diagnostic} (and {} more)", count - 1),
+                    };
+                    let all = diagnostics
+                        .iter()
+                        .map(|(label, diagnostic)| format!("{label}: {diagnostic}"))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.colored_label(ui.visuals().error_fg_color, text)
+                        .on_hover_text(all);
+                    ui.separator();
+                }
+                ui.label(&self.status);
+            });
+        });
+    }
+
+    fn draw_menu_bar(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::top("menu").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("Open…").clicked() {
+                        self.show_prompt(Prompt::Open);
+                        ui.close_menu();
+                    }
+                    if ui.button("Save").clicked() {
+                        match self.scene_path.clone() {
+                            Some(path) => self.save_scene(path),
+                            None => self.show_prompt(Prompt::SaveAs),
+                        }
+                        ui.close_menu();
+                    }
+                    if ui.button("Save As…").clicked() {
+                        self.show_prompt(Prompt::SaveAs);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Open Group Library…").clicked() {
+                        self.show_prompt(Prompt::OpenLibrary);
+                        ui.close_menu();
+                    }
+                    if ui.button("Save Group Library…").clicked() {
+                        self.show_prompt(Prompt::SaveLibrary);
+                        ui.close_menu();
+                    }
+                });
+                ui.menu_button("Edit", |ui| {
+                    let undo_button = egui::Button::new("Undo")
+                        .shortcut_text(ui.ctx().format_shortcut(&csg_node_graph::UNDO_SHORTCUT));
+                    if ui
+                        .add_enabled(self.csg_node_graph.can_undo(), undo_button)
+                        .clicked()
+                    {
+                        self.csg_node_graph.undo();
+                        ui.close_menu();
+                    }
+                    let redo_button = egui::Button::new("Redo")
+                        .shortcut_text(ui.ctx().format_shortcut(&csg_node_graph::REDO_SHORTCUT));
+                    if ui
+                        .add_enabled(self.csg_node_graph.can_redo(), redo_button)
+                        .clicked()
+                    {
+                        self.csg_node_graph.redo();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    // Pasting is only possible with the keyboard, egui reads the system clipboard
+                    // only when the paste shortcut is pressed.
+                    let copy_button = egui::Button::new("Copy")
+                        .shortcut_text(ui.ctx().format_shortcut(&csg_node_graph::COPY_SHORTCUT));
+                    if ui.add(copy_button).clicked() {
+                        if let Some(text) = self.csg_node_graph.copy_selection() {
+                            ui.ctx().output_mut(|output| output.copied_text = text);
+                        }
+                        ui.close_menu();
+                    }
+                    let cut_button = egui::Button::new("Cut")
+                        .shortcut_text(ui.ctx().format_shortcut(&csg_node_graph::CUT_SHORTCUT));
+                    if ui.add(cut_button).clicked() {
+                        if let Some(text) = self.csg_node_graph.cut_selection() {
+                            ui.ctx().output_mut(|output| output.copied_text = text);
+                        }
+                        ui.close_menu();
+                    }
+                    let duplicate_button = egui::Button::new("Duplicate").shortcut_text(
+                        ui.ctx()
+                            .format_shortcut(&csg_node_graph::DUPLICATE_SHORTCUT),
+                    );
+                    if ui.add(duplicate_button).clicked() {
+                        self.csg_node_graph.duplicate_selection();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Group Selected Nodes…").clicked() {
+                        self.show_prompt(Prompt::GroupName);
+                        ui.close_menu();
+                    }
+                });
+            });
+        });
+
+        let Some((prompt, text)) = &mut self.prompt else {
+            return;
+        };
+        let prompt = *prompt;
+        let (title, label, button) = match prompt {
+            Prompt::Open => ("Open scene", "Path:", "Open"),
+            Prompt::SaveAs => ("Save scene as", "Path:", "Save"),
+            Prompt::OpenLibrary => ("Open group library", "Path:", "Open"),
+            Prompt::SaveLibrary => ("Save group library", "Path:", "Save"),
+            Prompt::GroupName => ("Group selected nodes", "Name:", "Group"),
+        };
+        let mut open = true;
+        let mut confirmed = false;
+        egui::Window::new(title)
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(label);
+                    let response = ui.text_edit_singleline(text);
+                    confirmed = response.lost_focus()
+                        && ui.input(|input_state| input_state.key_pressed(egui::Key::Enter));
+                    confirmed |= ui.button(button).clicked();
+                });
+            });
+
+        if confirmed {
+            let text = self.prompt.take().unwrap().1;
+            match prompt {
+                Prompt::Open => self.open_scene(text.into()),
+                Prompt::SaveAs => self.save_scene(text.into()),
+                Prompt::OpenLibrary => self.open_group_library(text.into()),
+                Prompt::SaveLibrary => self.save_group_library(text.into()),
+                Prompt::GroupName => self.group_selection(text.trim()),
+            }
+        } else if !open {
+            self.prompt = None;
         }
     }
 }
 
 impl eframe::App for RayMarchingApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.draw_menu_bar(ctx);
+
+        egui::TopBottomPanel::top("mesh_import").show(ctx, |ui| {
+            self.mesh_import
+                .draw(ui, frame.wgpu_render_state().unwrap());
+        });
+
+        self.clock.update(ctx);
+        egui::TopBottomPanel::top("clock").show(ctx, |ui| {
+            self.clock.draw(ui);
+        });
+
+        self.draw_status_bar(ctx);
+
         egui::TopBottomPanel::bottom("node_graph")
             .resizable(true)
             .show(ctx, |ui| {
-                self.csg_node_graph.draw(ui);
+                if !self.group_edit_stack.is_empty() {
+                    ui.horizontal(|ui| {
+                        let names: Vec<&str> = self
+                            .group_edit_stack
+                            .iter()
+                            .map(|(name, _)| name.as_str())
+                            .collect();
+                        ui.label(format!("Editing group {}", names.join(" › ")));
+                        if ui.button("Done").clicked() {
+                            self.finish_group_edit();
+                        }
+                    });
+                }
+                egui::SidePanel::right("timeline")
+                    .resizable(true)
+                    .show_inside(ui, |ui| {
+                        self.csg_node_graph.draw_timeline(ui, self.clock.duration());
+                    });
+                self.csg_node_graph.draw(ui, self.clock.time());
             });
+        if let Some(status) = self.csg_node_graph.take_status() {
+            self.status = status;
+        }
+        if let Some(name) = self.csg_node_graph.take_group_to_edit() {
+            self.edit_group(name);
+        }
 
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::Frame::canvas(ui.style()).show(ui, |ui| {
@@ -71,12 +449,17 @@ impl eframe::App for RayMarchingApp {
                 ui.painter().add(egui_wgpu::Callback::new_paint_callback(
                     rect,
                     RayMarchingCallback::new(
-                        0.0,
-                        self.csg_node_graph.evaluate_root(),
+                        self.clock.time(),
+                        self.csg_node_graph
+                            .evaluate_root(self.clock.time(), self.mesh_import.has_grid()),
                         [rect.width(), rect.height()],
                         self.camera_controller.camera(),
                     ),
                 ));
+                // Text is rasterized in the background, evaluate again once it's done.
+                if TextGrid::is_rasterizing() {
+                    ctx.request_repaint();
+                }
             });
         });
     }
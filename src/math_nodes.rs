@@ -0,0 +1,196 @@
+use egui_node_graph::InputParamKind;
+
+use nalgebra::Vector3;
+
+use crate::csg_node_graph::{DataType, ValueType};
+use crate::diagnostics::{Diagnostic, InputParams};
+
+/// Nodes computing scalars and vectors on the CPU, to drive the parameters of other nodes.
+#[derive(Debug, Copy, Clone)]
+pub enum MathNodeTemplate {
+    // Inputs
+    Time,
+
+    // Constants
+    Scalar,
+    Vec3,
+
+    // Scalar math
+    Add,
+    Multiply,
+    Sin,
+    Clamp,
+    Lerp,
+
+    // Vector math
+    Length,
+    Normalize,
+    Dot,
+    Cross,
+    ComposeVec3,
+    SplitVec3,
+    /// Vector with all components set to a scalar.
+    Splat,
+}
+
+impl MathNodeTemplate {
+    pub fn all() -> impl IntoIterator<Item = Self> {
+        [
+            MathNodeTemplate::Time,
+            MathNodeTemplate::Scalar,
+            MathNodeTemplate::Vec3,
+            MathNodeTemplate::Add,
+            MathNodeTemplate::Multiply,
+            MathNodeTemplate::Sin,
+            MathNodeTemplate::Clamp,
+            MathNodeTemplate::Lerp,
+            MathNodeTemplate::Length,
+            MathNodeTemplate::Normalize,
+            MathNodeTemplate::Dot,
+            MathNodeTemplate::Cross,
+            MathNodeTemplate::ComposeVec3,
+            MathNodeTemplate::SplitVec3,
+            MathNodeTemplate::Splat,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MathNodeTemplate::Time => "Time",
+            MathNodeTemplate::Scalar => "Scalar",
+            MathNodeTemplate::Vec3 => "Vec3",
+            MathNodeTemplate::Add => "Add",
+            MathNodeTemplate::Multiply => "Multiply",
+            MathNodeTemplate::Sin => "Sin",
+            MathNodeTemplate::Clamp => "Clamp",
+            MathNodeTemplate::Lerp => "Lerp",
+            MathNodeTemplate::Length => "Length",
+            MathNodeTemplate::Normalize => "Normalize",
+            MathNodeTemplate::Dot => "Dot",
+            MathNodeTemplate::Cross => "Cross",
+            MathNodeTemplate::ComposeVec3 => "ComposeVec3",
+            MathNodeTemplate::SplitVec3 => "SplitVec3",
+            MathNodeTemplate::Splat => "Splat",
+        }
+    }
+
+    pub fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        let scalar = |name, value| {
+            (
+                name,
+                ValueType::Scalar(value),
+                InputParamKind::ConnectionOrConstant,
+            )
+        };
+        let vec3 = |name, value| {
+            (
+                name,
+                ValueType::Vec3(value),
+                InputParamKind::ConnectionOrConstant,
+            )
+        };
+
+        match self {
+            MathNodeTemplate::Time => vec![],
+            MathNodeTemplate::Scalar => vec![(
+                "value",
+                ValueType::Scalar(0.0),
+                InputParamKind::ConstantOnly,
+            )],
+            MathNodeTemplate::Vec3 => vec![(
+                "value",
+                ValueType::Vec3([0.0; 3]),
+                InputParamKind::ConstantOnly,
+            )],
+            MathNodeTemplate::Add => vec![scalar("a", 0.0), scalar("b", 0.0)],
+            MathNodeTemplate::Multiply => vec![scalar("a", 1.0), scalar("b", 1.0)],
+            MathNodeTemplate::Sin | MathNodeTemplate::Splat => vec![scalar("x", 0.0)],
+            MathNodeTemplate::Clamp => {
+                vec![scalar("x", 0.0), scalar("min", 0.0), scalar("max", 1.0)]
+            }
+            MathNodeTemplate::Lerp => vec![scalar("a", 0.0), scalar("b", 1.0), scalar("t", 0.5)],
+            MathNodeTemplate::Length | MathNodeTemplate::Normalize => {
+                vec![vec3("v", [1.0, 0.0, 0.0])]
+            }
+            MathNodeTemplate::Dot | MathNodeTemplate::Cross => {
+                vec![vec3("a", [1.0, 0.0, 0.0]), vec3("b", [0.0, 1.0, 0.0])]
+            }
+            MathNodeTemplate::ComposeVec3 => {
+                vec![scalar("x", 0.0), scalar("y", 0.0), scalar("z", 0.0)]
+            }
+            MathNodeTemplate::SplitVec3 => vec![vec3("v", [0.0; 3])],
+        }
+    }
+
+    pub fn outputs(&self) -> Vec<(&'static str, DataType)> {
+        match self {
+            MathNodeTemplate::Time
+            | MathNodeTemplate::Scalar
+            | MathNodeTemplate::Add
+            | MathNodeTemplate::Multiply
+            | MathNodeTemplate::Sin
+            | MathNodeTemplate::Clamp
+            | MathNodeTemplate::Lerp
+            | MathNodeTemplate::Length
+            | MathNodeTemplate::Dot => vec![("value", DataType::Scalar)],
+            MathNodeTemplate::Vec3
+            | MathNodeTemplate::Normalize
+            | MathNodeTemplate::Cross
+            | MathNodeTemplate::ComposeVec3
+            | MathNodeTemplate::Splat => vec![("value", DataType::Vec3)],
+            MathNodeTemplate::SplitVec3 => vec![
+                ("x", DataType::Scalar),
+                ("y", DataType::Scalar),
+                ("z", DataType::Scalar),
+            ],
+        }
+    }
+
+    /// Compute the values of the outputs, in the order of [`MathNodeTemplate::outputs`].
+    /// `time` is the time of the animation clock in seconds.
+    pub fn evaluate(
+        &self,
+        input_params: &InputParams,
+        time: f32,
+    ) -> Result<Vec<ValueType>, Diagnostic> {
+        let scalar = |name: &str| input_params.scalar(name);
+        let vec3 = |name: &str| input_params.vec3(name).map(Vector3::from);
+
+        Ok(match self {
+            MathNodeTemplate::Time => vec![ValueType::Scalar(time)],
+            MathNodeTemplate::Scalar => vec![ValueType::Scalar(scalar("value")?)],
+            MathNodeTemplate::Vec3 => vec![ValueType::Vec3(vec3("value")?.into())],
+            MathNodeTemplate::Add => vec![ValueType::Scalar(scalar("a")? + scalar("b")?)],
+            MathNodeTemplate::Multiply => vec![ValueType::Scalar(scalar("a")? * scalar("b")?)],
+            MathNodeTemplate::Sin => vec![ValueType::Scalar(scalar("x")?.sin())],
+            MathNodeTemplate::Clamp => {
+                // Unlike `f32::clamp`, doesn't panic when min > max.
+                let x = scalar("x")?.max(scalar("min")?).min(scalar("max")?);
+                vec![ValueType::Scalar(x)]
+            }
+            MathNodeTemplate::Lerp => {
+                let (a, b, t) = (scalar("a")?, scalar("b")?, scalar("t")?);
+                vec![ValueType::Scalar(a + (b - a) * t)]
+            }
+            MathNodeTemplate::Length => vec![ValueType::Scalar(vec3("v")?.norm())],
+            MathNodeTemplate::Normalize => {
+                let v = vec3("v")?.try_normalize(f32::EPSILON).unwrap_or_default();
+                vec![ValueType::Vec3(v.into())]
+            }
+            MathNodeTemplate::Dot => vec![ValueType::Scalar(vec3("a")?.dot(&vec3("b")?))],
+            MathNodeTemplate::Cross => vec![ValueType::Vec3(vec3("a")?.cross(&vec3("b")?).into())],
+            MathNodeTemplate::ComposeVec3 => {
+                vec![ValueType::Vec3([scalar("x")?, scalar("y")?, scalar("z")?])]
+            }
+            MathNodeTemplate::SplitVec3 => {
+                let v = vec3("v")?;
+                vec![
+                    ValueType::Scalar(v.x),
+                    ValueType::Scalar(v.y),
+                    ValueType::Scalar(v.z),
+                ]
+            }
+            MathNodeTemplate::Splat => vec![ValueType::Vec3([scalar("x")?; 3])],
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+
+use eframe::{egui, egui_wgpu};
+
+use crate::ray_marching::renderer::RayMarchingResources;
+use crate::ray_marching::sdf_grid::{MeshLoadError, SdfGrid};
+
+/// Panel for baking an OBJ or STL mesh into the grid sampled by the `GridSdf` node.
+pub(crate) struct MeshImport {
+    path: String,
+    /// Number of samples along the longest axis of the grid.
+    resolution: u32,
+    bake_job: Option<JoinHandle<Result<SdfGrid, MeshLoadError>>>,
+    status: String,
+    /// Whether a grid was baked in this session. Grids aren't saved with scenes.
+    has_grid: bool,
+}
+
+impl Default for MeshImport {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            resolution: 64,
+            bake_job: None,
+            status: String::new(),
+            has_grid: false,
+        }
+    }
+}
+
+impl MeshImport {
+    pub(crate) fn has_grid(&self) -> bool {
+        self.has_grid
+    }
+
+    pub(crate) fn draw(&mut self, ui: &mut egui::Ui, render_state: &egui_wgpu::RenderState) {
+        if self.bake_job.as_ref().is_some_and(|job| job.is_finished()) {
+            match self.bake_job.take().unwrap().join() {
+                Ok(Ok(grid)) => {
+                    let [x, y, z] = grid.resolution;
+                    self.status = format!("Baked {x}×{y}×{z} grid");
+                    render_state
+                        .renderer
+                        .write()
+                        .callback_resources
+                        .get_mut::<RayMarchingResources>()
+                        .unwrap()
+                        .upload_grid_sdf(&render_state.device, &render_state.queue, &grid);
+                    self.has_grid = true;
+                }
+                Ok(Err(err)) => self.status = format!("Error: {err}"),
+                // The mesh parsers can panic on malformed files.
+                Err(_) => self.status = "Error: the mesh could not be baked".to_string(),
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Mesh (OBJ/STL):");
+            ui.text_edit_singleline(&mut self.path);
+            ui.label("Resolution:");
+            ui.add(egui::DragValue::new(&mut self.resolution).clamp_range(8..=256));
+
+            if self.bake_job.is_some() {
+                ui.spinner();
+                ui.label("Baking…");
+                // Keep polling the job.
+                ui.ctx().request_repaint();
+            } else {
+                if ui.button("Bake").clicked() {
+                    let path = PathBuf::from(&self.path);
+                    let resolution = self.resolution;
+                    self.bake_job = Some(std::thread::spawn(move || {
+                        SdfGrid::bake_mesh_file(&path, resolution)
+                    }));
+                }
+                ui.label(&self.status);
+            }
+        });
+    }
+}
@@ -0,0 +1,223 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::csg_node_graph::{CSGNodeGraph, DataType, NodeTemplate};
+use crate::scene::{SceneError, SceneGraph, SceneValue};
+
+/// Version written to new group library files. Bump it when the format changes incompatibly.
+pub(crate) const GROUP_LIBRARY_VERSION: u32 = 1;
+
+/// Library shared by the node graphs, so that every graph sees edits of a group's definition.
+pub(crate) type SharedGroupLibrary = Rc<RefCell<GroupLibrary>>;
+
+/// Definitions of node groups by name. A definition is a node graph with `Group Input` nodes for
+/// the inputs of the group, and a `Group Output` node for its SDF.
+#[derive(Default)]
+pub(crate) struct GroupLibrary {
+    definitions: BTreeMap<String, SceneGraph>,
+    /// Definitions built into node graphs for evaluating group nodes.
+    compiled: HashMap<String, Rc<CSGNodeGraph>>,
+}
+
+/// Input of a group node, defined by a `Group Input` node in the group's definition.
+#[derive(Debug, Clone)]
+pub(crate) struct GroupInterfaceInput {
+    pub(crate) name: String,
+    pub(crate) data_type: DataType,
+    pub(crate) default: Option<SceneValue>,
+}
+
+/// Contents of a group library file, stored as RON.
+#[derive(Serialize, Deserialize)]
+struct GroupLibraryFile {
+    version: u32,
+    groups: BTreeMap<String, SceneGraph>,
+}
+
+/// Only the version of a group library file, see [`crate::scene::Scene::load`].
+#[derive(Deserialize)]
+#[serde(rename = "GroupLibraryFile")]
+struct GroupLibraryVersion {
+    version: u32,
+}
+
+impl GroupLibrary {
+    pub(crate) fn names(&self) -> impl Iterator<Item = &String> {
+        self.definitions.keys()
+    }
+
+    pub(crate) fn definition(&self, name: &str) -> Option<&SceneGraph> {
+        self.definitions.get(name)
+    }
+
+    pub(crate) fn definitions(&self) -> &BTreeMap<String, SceneGraph> {
+        &self.definitions
+    }
+
+    /// Add or replace a definition, unless the group would contain itself.
+    pub(crate) fn set_definition(
+        &mut self,
+        name: &str,
+        definition: SceneGraph,
+    ) -> Result<(), String> {
+        if self.uses(&definition, name) {
+            return Err(format!("the group {name:?} can't contain itself"));
+        }
+        self.definitions.insert(name.to_string(), definition);
+        // Groups can contain other groups, so any compiled group may be outdated.
+        self.compiled.clear();
+        Ok(())
+    }
+
+    /// Inputs of a group, ordered top to bottom like the `Group Input` nodes in its definition.
+    pub(crate) fn interface(&self, name: &str) -> Vec<GroupInterfaceInput> {
+        let Some(definition) = self.definitions.get(name) else {
+            return Vec::new();
+        };
+
+        let mut group_inputs: Vec<_> = definition
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let data_type = DataType::ALL.into_iter().find(|data_type| {
+                    NodeTemplate::GroupInput(*data_type).name() == node.template
+                })?;
+                let value = |input_name: &str| {
+                    node.inputs
+                        .iter()
+                        .find(|input| input.name == input_name)
+                        .and_then(|input| input.value.clone())
+                };
+                let Some(SceneValue::Text(name)) = value("name") else {
+                    return None;
+                };
+                let input = GroupInterfaceInput {
+                    name,
+                    data_type,
+                    default: value("default"),
+                };
+                Some((node.position[1], input))
+            })
+            .collect();
+        group_inputs.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let mut interface: Vec<GroupInterfaceInput> = Vec::new();
+        for (_, input) in group_inputs {
+            // Inputs are looked up by name, so only the first of several with the same name counts.
+            if !input.name.is_empty() && interface.iter().all(|other| other.name != input.name) {
+                interface.push(input);
+            }
+        }
+        interface
+    }
+
+    /// The definition of a group built into a node graph, shared until the library changes.
+    pub(crate) fn compiled(groups: &SharedGroupLibrary, name: &str) -> Option<Rc<CSGNodeGraph>> {
+        if let Some(compiled) = groups.borrow().compiled.get(name) {
+            return Some(compiled.clone());
+        }
+
+        let definition = groups.borrow().definitions.get(name)?.clone();
+        let compiled = Rc::new(CSGNodeGraph::from_scene_graph(&definition, groups.clone()).ok()?);
+        groups
+            .borrow_mut()
+            .compiled
+            .insert(name.to_string(), compiled.clone());
+        Some(compiled)
+    }
+
+    /// Add the groups of a library file, replacing groups of the same name.
+    pub(crate) fn load(&mut self, path: &Path) -> Result<(), SceneError> {
+        let text = std::fs::read_to_string(path)?;
+        let GroupLibraryVersion { version } = ron::from_str(&text)?;
+        if version > GROUP_LIBRARY_VERSION {
+            return Err(SceneError::UnsupportedVersion(version));
+        }
+        let file: GroupLibraryFile = ron::from_str(&text)?;
+        self.merge(file.groups)
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<(), SceneError> {
+        let file = GroupLibraryFile {
+            version: GROUP_LIBRARY_VERSION,
+            groups: self.definitions.clone(),
+        };
+        let text = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Add definitions, replacing definitions of the same name.
+    pub(crate) fn merge(
+        &mut self,
+        definitions: BTreeMap<String, SceneGraph>,
+    ) -> Result<(), SceneError> {
+        for name in definitions.keys() {
+            check_name(name)?;
+        }
+        self.definitions.extend(definitions);
+        self.compiled.clear();
+        Ok(())
+    }
+
+    /// Add definitions of groups the library doesn't have yet, keeping existing definitions.
+    pub(crate) fn merge_missing(
+        &mut self,
+        definitions: BTreeMap<String, SceneGraph>,
+    ) -> Result<(), SceneError> {
+        for name in definitions.keys() {
+            check_name(name)?;
+        }
+        for (name, definition) in definitions {
+            if !self.definitions.contains_key(&name) {
+                // Groups containing themselves are left out, their nodes show as broken groups.
+                let _ = self.set_definition(&name, definition);
+            }
+        }
+        Ok(())
+    }
+
+    /// Definitions of the groups used by a graph, including groups used by those groups.
+    pub(crate) fn used_by(&self, graph: &SceneGraph) -> BTreeMap<String, SceneGraph> {
+        let mut used = BTreeMap::new();
+        let mut graphs = vec![graph];
+        while let Some(graph) = graphs.pop() {
+            for node in &graph.nodes {
+                if let Some(definition) = self.definitions.get(&node.template) {
+                    if !used.contains_key(&node.template) {
+                        used.insert(node.template.clone(), definition.clone());
+                        graphs.push(definition);
+                    }
+                }
+            }
+        }
+        used
+    }
+
+    /// Whether a graph uses the group `name`, directly or through other groups.
+    pub(crate) fn uses(&self, graph: &SceneGraph, name: &str) -> bool {
+        let uses_directly =
+            |graph: &SceneGraph| graph.nodes.iter().any(|node| node.template == name);
+        uses_directly(graph) || self.used_by(graph).values().any(uses_directly)
+    }
+}
+
+/// Reject group names that couldn't be told apart from built-in node types, since node types are
+/// looked up by name and built-in types are found first.
+fn check_name(name: &str) -> Result<(), SceneError> {
+    let reason = if name.is_empty() {
+        "the group needs a name"
+    } else if NodeTemplate::from_name(name, &GroupLibrary::default()).is_some() {
+        "there already is a node type of that name"
+    } else {
+        return Ok(());
+    };
+    Err(SceneError::BadGroup {
+        group: name.to_string(),
+        reason: reason.to_string(),
+    })
+}
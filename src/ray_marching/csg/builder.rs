@@ -1,3 +1,7 @@
+use std::sync::Arc;
+
+use crate::ray_marching::csg::TextGrid;
+
 #[derive(Debug)]
 #[repr(u32)]
 pub enum CSGCommandType {
@@ -5,27 +9,110 @@ pub enum CSGCommandType {
     // (0 children, no space transform)
     Sphere = 0,
     Box,
-    // Plane,
+    Plane,
+    Torus,
+    Cylinder,
+    Capsule,
+    Cone,
+    Ellipsoid,
+    RoundedBox,
+    Octahedron,
+    Polyline,
+    QuadraticBezier,
+    CubicBezier,
+    GridSdf,
+
+    // Fractals
+    // (0 children, no space transform)
+    Mandelbulb = 50,
+    MengerSponge,
+    SierpinskiTetrahedron,
+    Mandelbox,
 
     // Binary operators
-    // (2 children, no space transform)
+    // (2 children, or any number for Union, Intersection and SmoothUnion, no space transform)
     Union = 100,
     Subtraction,
-    // Intersection,
+    Intersection,
+    SmoothUnion,
+    SmoothSubtraction,
+    SmoothIntersection,
+    Morph,
+    ChamferUnion,
+    StairsUnion,
+    StairsSubtraction,
+    ColumnsUnion,
+    Groove,
+    Tongue,
+    Xor,
 
     // Space transformations
     // (1 child, transforms space)
-    // TranslationPush = 200,
-    // TranslationPop,
-    // RotationPush,
-    // RotationPop,
-    // ScalePush,
-    // ScalePop,
+    TranslationPush = 200,
+    TranslationPop,
+    RotationPush,
+    RotationPop,
+    ScalePush,
+    ScalePop,
+    RepeatPush,
+    RepeatPop,
+    MirrorPush,
+    MirrorPop,
+    RadialSymmetryPush,
+    RadialSymmetryPop,
+    TwistPush,
+    TwistPop,
+    BendPush,
+    BendPop,
+    TaperPush,
+    TaperPop,
+    ElongatePush,
+    ElongatePop,
+    ExtrudePush,
+    ExtrudePop,
+    RevolvePush,
+    RevolvePop,
+
+    // Distance modifiers
+    // (1 child, no space transform)
+    SineDisplace = 300,
+    Onion,
+    Offset,
+    Displace,
+
+    // 2D primitives
+    // (0 children, evaluated on the XY plane)
+    Circle = 400,
+    Rectangle,
+    RegularPolygon,
+    RoundedRectangle,
+    Text,
+    Polygon,
 }
 
+/// Size of the stack of distances in the shader, `value_stack_max_size` in `ray_marching.wgsl`.
+pub const VALUE_STACK_MAX_SIZE: usize = 32;
+/// Size of the stack of positions in the shader, `pos_stack_max_size` in `ray_marching.wgsl`.
+pub const POS_STACK_MAX_SIZE: usize = 16;
+/// Largest width and height of the text atlas texture, wgpu's default `max_texture_dimension_2d`.
+pub const TEXT_ATLAS_MAX_SIZE: u32 = 8192;
+
 pub struct CSGCommandBufferBuilder {
     pub cmd_count: u32,
     pub buffer: Vec<u32>,
+    /// Upper bound on the Lipschitz constant of the scene's SDF.
+    /// The ray marcher divides its steps by this to avoid overshooting the surface.
+    pub lipschitz: f32,
+    /// Lipschitz constant of the deformations enclosing the commands currently being pushed.
+    enclosing_lipschitz: f32,
+    /// Largest number of distances on the shader's stack while evaluating the commands.
+    pub max_value_stack_size: usize,
+    /// Largest number of positions on the shader's stack while evaluating the commands.
+    pub max_pos_stack_size: usize,
+    value_stack_size: usize,
+    pos_stack_size: usize,
+    /// Grids of the text nodes, stacked vertically in this order into the text atlas texture.
+    pub(crate) text_grids: Vec<Arc<TextGrid>>,
 }
 
 impl CSGCommandBufferBuilder {
@@ -33,14 +120,39 @@ impl CSGCommandBufferBuilder {
         Self {
             cmd_count: 0,
             buffer: Vec::new(),
+            lipschitz: 1.0,
+            enclosing_lipschitz: 1.0,
+            max_value_stack_size: 0,
+            max_pos_stack_size: 0,
+            value_stack_size: 0,
+            pos_stack_size: 0,
+            text_grids: Vec::new(),
         }
     }
 
     /// Push a command onto the command stack.
     /// Must be called before pushing the command parameters.
     pub fn push_command(&mut self, cmd_type: CSGCommandType) -> &mut Self {
+        let cmd = cmd_type as u32;
+
+        // Track the stack sizes the shader needs, see the groups of commands above.
+        match cmd {
+            100..=199 => self.value_stack_size = self.value_stack_size.saturating_sub(1),
+            // Space transformations alternate between pushing and popping a position.
+            200..=299 if cmd % 2 == 0 => {
+                self.pos_stack_size += 1;
+                self.max_pos_stack_size = self.max_pos_stack_size.max(self.pos_stack_size);
+            }
+            200..=299 => self.pos_stack_size = self.pos_stack_size.saturating_sub(1),
+            300..=399 => {}
+            _ => {
+                self.value_stack_size += 1;
+                self.max_value_stack_size = self.max_value_stack_size.max(self.value_stack_size);
+            }
+        }
+
         self.cmd_count += 1;
-        self.buffer.push(cmd_type as u32);
+        self.buffer.push(cmd);
         self
     }
 
@@ -53,10 +165,88 @@ impl CSGCommandBufferBuilder {
         self
     }
 
+    /// Push a GLSL mat3x3 param onto the parameter stack, in column-major order.
+    /// Must be called after pushing the command.
+    pub fn push_param_mat3(&mut self, value: [[f32; 3]; 3]) -> &mut Self {
+        for column in value {
+            self.push_param_vec3(column);
+        }
+        self
+    }
+
+    /// Push a variable-length list of GLSL vec2 params onto the parameter stack, preceded by its
+    /// length as a u32. Must be called after pushing the command.
+    pub fn push_param_vec2_list(&mut self, values: &[[f32; 2]]) -> &mut Self {
+        self.push_param_uint(values.len() as u32);
+        for value in values.iter().flatten() {
+            self.push_param_float(*value);
+        }
+        self
+    }
+
+    /// Push a variable-length list of GLSL vec3 params onto the parameter stack, preceded by its
+    /// length as a u32. Must be called after pushing the command.
+    pub fn push_param_vec3_list(&mut self, values: &[[f32; 3]]) -> &mut Self {
+        self.push_param_uint(values.len() as u32);
+        for value in values {
+            self.push_param_vec3(*value);
+        }
+        self
+    }
+
+    /// Height of the text atlas holding the grids of all text nodes.
+    pub fn text_atlas_height(&self) -> u32 {
+        self.text_grids.iter().map(|grid| grid.height()).sum()
+    }
+
+    /// Add a text grid to the text atlas, unless already added, and push the atlas row of its
+    /// first sample as a u32 param. Must be called after pushing the command.
+    pub fn push_param_text_grid(&mut self, grid: &Arc<TextGrid>) -> &mut Self {
+        let index = match self.text_grids.iter().position(|g| Arc::ptr_eq(g, grid)) {
+            Some(index) => index,
+            None => {
+                self.text_grids.push(grid.clone());
+                self.text_grids.len() - 1
+            }
+        };
+        let row = self.text_grids[..index].iter().map(|g| g.height()).sum();
+        self.push_param_uint(row)
+    }
+
+    /// Push the number of children of a variadic operator as a u32 param, its command folds all
+    /// of them. Must be called right after pushing the command.
+    pub fn push_param_child_count(&mut self, count: u32) -> &mut Self {
+        // `push_command` counted the operator as binary.
+        let extra_children = count.saturating_sub(2) as usize;
+        self.value_stack_size = self.value_stack_size.saturating_sub(extra_children);
+        self.push_param_uint(count)
+    }
+
+    /// Push a GLSL u32 param onto the parameter stack.
+    /// Must be called after pushing the command.
+    pub fn push_param_uint(&mut self, value: u32) -> &mut Self {
+        self.buffer.push(value);
+        self
+    }
+
     /// Push a GLSL float param onto the parameter stack.
     /// Must be called after pushing the command.
     pub fn push_param_float(&mut self, value: f32) -> &mut Self {
         self.buffer.push(value.to_bits());
         self
     }
+
+    /// Enter a deformation with the given Lipschitz constant.
+    /// Must be called before building the commands of the deformed children.
+    pub fn push_lipschitz(&mut self, lipschitz: f32) -> &mut Self {
+        self.enclosing_lipschitz *= lipschitz;
+        self.lipschitz = self.lipschitz.max(self.enclosing_lipschitz);
+        self
+    }
+
+    /// Leave a deformation entered with [`Self::push_lipschitz`].
+    pub fn pop_lipschitz(&mut self, lipschitz: f32) -> &mut Self {
+        self.enclosing_lipschitz /= lipschitz;
+        self
+    }
 }
@@ -0,0 +1,85 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Bends the child around the Z axis, curving the X axis towards the Y axis.
+#[derive(Debug, Clone)]
+pub struct Bend {
+    /// Rotation in degrees per unit along the X axis.
+    pub(crate) angle: f32,
+    /// Distance from the origin up to which the child is expected to extend.
+    /// Only used to estimate how much the deformation stretches distances.
+    pub(crate) radius: f32,
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl Bend {
+    /// Points at `radius` from the X axis move `angle * radius` sideways per unit along the axis.
+    fn lipschitz(&self) -> f32 {
+        let rate = self.angle.to_radians() * self.radius;
+        (1. + rate * rate).sqrt()
+    }
+}
+
+impl BuildCommands for Bend {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::BendPush)
+            .push_param_float(self.angle.to_radians());
+        builder.push_lipschitz(self.lipschitz());
+        self.child.build_commands(builder);
+        builder.pop_lipschitz(self.lipschitz());
+        builder.push_command(CSGCommandType::BendPop);
+    }
+}
+
+impl SignedDistance for Bend {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct BendTemplate;
+impl CSGNodeTemplateTrait for BendTemplate {
+    fn name(&self) -> &'static str {
+        "Bend"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "angle",
+                ValueType::Scalar(45.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "radius",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let angle = input_params.scalar("angle")?;
+        let radius = input_params.scalar("radius")?;
+        Ok(Bend {
+            angle,
+            radius,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
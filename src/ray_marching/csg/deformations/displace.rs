@@ -0,0 +1,104 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Estimated upper bound on the gradient of a single octave of the shader's gradient noise.
+const NOISE_LIPSCHITZ: f32 = 3.;
+
+/// Displaces the surface of the child by fractal Brownian motion (FBM) of 3D gradient noise.
+/// Each octave doubles the frequency and halves the amplitude of the previous one.
+#[derive(Debug, Clone)]
+pub struct Displace {
+    pub(crate) amplitude: f32,
+    pub(crate) frequency: f32,
+    pub(crate) octaves: u32,
+    pub(crate) seed: u32,
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl Displace {
+    /// Every octave contributes the same gradient, as doubling the frequency cancels out halving
+    /// the amplitude.
+    fn lipschitz(&self) -> f32 {
+        1. + (self.amplitude * self.frequency).abs() * NOISE_LIPSCHITZ * self.octaves as f32
+    }
+}
+
+impl BuildCommands for Displace {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder.push_lipschitz(self.lipschitz());
+        self.child.build_commands(builder);
+        builder.pop_lipschitz(self.lipschitz());
+        builder
+            .push_command(CSGCommandType::Displace)
+            .push_param_float(self.amplitude)
+            .push_param_float(self.frequency)
+            .push_param_uint(self.octaves)
+            .push_param_uint(self.seed);
+    }
+}
+
+impl SignedDistance for Displace {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct DisplaceTemplate;
+impl CSGNodeTemplateTrait for DisplaceTemplate {
+    fn name(&self) -> &'static str {
+        "Displace"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "amplitude",
+                ValueType::Scalar(0.1),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "frequency",
+                ValueType::Scalar(2.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "octaves",
+                ValueType::Scalar(4.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "seed",
+                ValueType::Scalar(0.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let amplitude = input_params.scalar("amplitude")?;
+        let frequency = input_params.scalar("frequency")?;
+        let octaves = input_params.scalar("octaves")?;
+        let seed = input_params.scalar("seed")?;
+        Ok(Displace {
+            amplitude,
+            frequency,
+            octaves: octaves.round().max(1.) as u32,
+            seed: seed.round().max(0.) as u32,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
@@ -0,0 +1,11 @@
+mod bend;
+mod displace;
+mod sine_displace;
+mod taper;
+mod twist;
+
+pub use bend::*;
+pub use displace::*;
+pub use sine_displace::*;
+pub use taper::*;
+pub use twist::*;
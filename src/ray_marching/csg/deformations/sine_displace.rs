@@ -0,0 +1,81 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Displaces the surface of the child by a product of sine waves along each axis.
+#[derive(Debug, Clone)]
+pub struct SineDisplace {
+    pub(crate) amplitude: f32,
+    pub(crate) frequency: f32,
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl SineDisplace {
+    /// The gradient of the displacement is at most `amplitude * frequency` along each axis.
+    fn lipschitz(&self) -> f32 {
+        1. + (self.amplitude * self.frequency).abs() * 3f32.sqrt()
+    }
+}
+
+impl BuildCommands for SineDisplace {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder.push_lipschitz(self.lipschitz());
+        self.child.build_commands(builder);
+        builder.pop_lipschitz(self.lipschitz());
+        builder
+            .push_command(CSGCommandType::SineDisplace)
+            .push_param_float(self.amplitude)
+            .push_param_float(self.frequency);
+    }
+}
+
+impl SignedDistance for SineDisplace {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SineDisplaceTemplate;
+impl CSGNodeTemplateTrait for SineDisplaceTemplate {
+    fn name(&self) -> &'static str {
+        "SineDisplace"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "amplitude",
+                ValueType::Scalar(0.05),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "frequency",
+                ValueType::Scalar(10.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let amplitude = input_params.scalar("amplitude")?;
+        let frequency = input_params.scalar("frequency")?;
+        Ok(SineDisplace {
+            amplitude,
+            frequency,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
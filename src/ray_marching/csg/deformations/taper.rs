@@ -0,0 +1,90 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Smallest scale of the cross-section, must match `taper_min_scale` in the shader.
+const MIN_SCALE: f32 = 0.1;
+
+/// Scales the cross-section of the child in the XZ plane linearly along the Y axis.
+#[derive(Debug, Clone)]
+pub struct Taper {
+    /// Change in scale per unit along the Y axis, the scale is 1 at the origin.
+    pub(crate) rate: f32,
+    /// Distance from the origin up to which the child is expected to extend.
+    /// Only used to estimate how much the deformation stretches distances.
+    pub(crate) radius: f32,
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl Taper {
+    /// Within `radius` of the origin, space is compressed by at most the smallest scale, and
+    /// sheared by the change in scale times the distance from the axis.
+    fn lipschitz(&self) -> f32 {
+        let min_scale = (1. - self.rate.abs() * self.radius).max(MIN_SCALE);
+        let shear = self.rate.abs() * self.radius / (min_scale * min_scale);
+        (1. / (min_scale * min_scale) + shear * shear).sqrt()
+    }
+}
+
+impl BuildCommands for Taper {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::TaperPush)
+            .push_param_float(self.rate);
+        builder.push_lipschitz(self.lipschitz());
+        self.child.build_commands(builder);
+        builder.pop_lipschitz(self.lipschitz());
+        builder.push_command(CSGCommandType::TaperPop);
+    }
+}
+
+impl SignedDistance for Taper {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TaperTemplate;
+impl CSGNodeTemplateTrait for TaperTemplate {
+    fn name(&self) -> &'static str {
+        "Taper"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "rate",
+                ValueType::Scalar(0.25),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "radius",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let rate = input_params.scalar("rate")?;
+        let radius = input_params.scalar("radius")?;
+        Ok(Taper {
+            rate,
+            radius,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
@@ -0,0 +1,66 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::fractals::MAX_ITERATIONS;
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Mandelbox fractal.
+/// See: https://en.wikipedia.org/wiki/Mandelbox
+#[derive(Debug, Clone)]
+pub struct Mandelbox {
+    pub(crate) iterations: u32,
+    /// Scale factor applied after folding in each iteration.
+    pub(crate) scale: f32,
+}
+
+impl BuildCommands for Mandelbox {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Mandelbox)
+            .push_param_uint(self.iterations)
+            .push_param_float(self.scale);
+    }
+}
+
+impl SignedDistance for Mandelbox {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MandelboxTemplate;
+impl CSGNodeTemplateTrait for MandelboxTemplate {
+    fn name(&self) -> &'static str {
+        "Mandelbox"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "iterations",
+                ValueType::Scalar(10.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "scale",
+                ValueType::Scalar(2.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let iterations = input_params.scalar("iterations")?;
+        let scale = input_params.scalar("scale")?;
+        Ok(Mandelbox {
+            iterations: iterations.round().clamp(0., MAX_ITERATIONS) as u32,
+            scale,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,65 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::fractals::MAX_ITERATIONS;
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Mandelbulb fractal, roughly fitting in a sphere with a radius of 1.2.
+/// See: https://iquilezles.org/articles/mandelbulb/
+#[derive(Debug, Clone)]
+pub struct Mandelbulb {
+    pub(crate) iterations: u32,
+    pub(crate) power: f32,
+}
+
+impl BuildCommands for Mandelbulb {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Mandelbulb)
+            .push_param_uint(self.iterations)
+            .push_param_float(self.power);
+    }
+}
+
+impl SignedDistance for Mandelbulb {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MandelbulbTemplate;
+impl CSGNodeTemplateTrait for MandelbulbTemplate {
+    fn name(&self) -> &'static str {
+        "Mandelbulb"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "iterations",
+                ValueType::Scalar(8.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "power",
+                ValueType::Scalar(8.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let iterations = input_params.scalar("iterations")?;
+        let power = input_params.scalar("power")?;
+        Ok(Mandelbulb {
+            iterations: iterations.round().clamp(0., MAX_ITERATIONS) as u32,
+            power,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,54 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::fractals::MAX_ITERATIONS;
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Menger sponge fractal, carved out of a box with a radius of 1.
+/// See: https://iquilezles.org/articles/menger/
+#[derive(Debug, Clone)]
+pub struct MengerSponge {
+    pub(crate) iterations: u32,
+}
+
+impl BuildCommands for MengerSponge {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::MengerSponge)
+            .push_param_uint(self.iterations);
+    }
+}
+
+impl SignedDistance for MengerSponge {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MengerSpongeTemplate;
+impl CSGNodeTemplateTrait for MengerSpongeTemplate {
+    fn name(&self) -> &'static str {
+        "MengerSponge"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![(
+            "iterations",
+            ValueType::Scalar(4.),
+            InputParamKind::ConnectionOrConstant,
+        )]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let iterations = input_params.scalar("iterations")?;
+        Ok(MengerSponge {
+            iterations: iterations.round().clamp(0., MAX_ITERATIONS) as u32,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,13 @@
+mod mandelbox;
+mod mandelbulb;
+mod menger_sponge;
+mod sierpinski_tetrahedron;
+
+pub use mandelbox::*;
+pub use mandelbulb::*;
+pub use menger_sponge::*;
+pub use sierpinski_tetrahedron::*;
+
+/// Most iterations a fractal runs. The shader iterates for every step of every pixel, so larger
+/// counts stall the GPU long enough for the driver to reset it.
+const MAX_ITERATIONS: f32 = 32.;
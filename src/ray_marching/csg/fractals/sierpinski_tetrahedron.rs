@@ -0,0 +1,65 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::fractals::MAX_ITERATIONS;
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Sierpinski tetrahedron fractal, with its corners at alternating corners of a box with a radius of 1.
+#[derive(Debug, Clone)]
+pub struct SierpinskiTetrahedron {
+    pub(crate) iterations: u32,
+    /// Scale of the whole tetrahedron relative to each of its copies.
+    pub(crate) scale: f32,
+}
+
+impl BuildCommands for SierpinskiTetrahedron {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::SierpinskiTetrahedron)
+            .push_param_uint(self.iterations)
+            .push_param_float(self.scale);
+    }
+}
+
+impl SignedDistance for SierpinskiTetrahedron {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SierpinskiTetrahedronTemplate;
+impl CSGNodeTemplateTrait for SierpinskiTetrahedronTemplate {
+    fn name(&self) -> &'static str {
+        "SierpinskiTetrahedron"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "iterations",
+                ValueType::Scalar(8.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "scale",
+                ValueType::Scalar(2.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let iterations = input_params.scalar("iterations")?;
+        let scale = input_params.scalar("scale")?;
+        Ok(SierpinskiTetrahedron {
+            iterations: iterations.round().clamp(0., MAX_ITERATIONS) as u32,
+            scale,
+        }
+        .into())
+    }
+}
@@ -1,47 +1,120 @@
-use std::collections::HashMap;
-
 use egui_node_graph::InputParamKind;
 use enum_dispatch::enum_dispatch;
 
+pub use deformations::*;
+pub use fractals::*;
+pub use modifiers::*;
 pub use operations::*;
 pub use primitives::*;
+pub use sdf_2d::*;
+pub use transformations::*;
 
 use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
 use crate::ray_marching::csg::builder::CSGCommandBufferBuilder;
 
 pub(crate) mod builder;
+mod deformations;
+mod fractals;
+mod modifiers;
 mod operations;
 mod primitives;
+mod sdf_2d;
+mod transformations;
 
 #[enum_dispatch]
 pub trait BuildCommands {
     fn build_commands(&self, builder: &mut CSGCommandBufferBuilder);
 }
 
+/// Whether an SDF evaluates to the exact distance to its surface, or only to a lower bound of it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DistanceKind {
+    Exact,
+    Bound,
+}
+
+#[enum_dispatch]
+pub trait SignedDistance {
+    fn distance_kind(&self) -> DistanceKind;
+}
+
 #[enum_dispatch]
 pub trait CSGNodeTemplateTrait {
     fn name(&self) -> &'static str;
     fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)>;
-    fn evaluate(&self, input_params: HashMap<String, ValueType>) -> Option<CSGNode>;
+    /// Whether the user can add and remove copies of the first input, named after
+    /// [`VARIADIC_INPUT_NAMES`].
+    fn is_variadic(&self) -> bool {
+        false
+    }
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic>;
 }
 
-#[enum_dispatch(BuildCommands)]
+#[enum_dispatch(BuildCommands, SignedDistance)]
 #[derive(Debug, Clone)]
 pub enum CSGNode {
     // Primitives
     Sphere,
     Box,
-    // Plane,
+    Plane,
+    Torus,
+    Cylinder,
+    Capsule,
+    Cone,
+    Ellipsoid,
+    RoundedBox,
+    Octahedron,
+    Polyline,
+    QuadraticBezier,
+    CubicBezier,
+    GridSdf,
+
+    // Fractals
+    Mandelbulb,
+    MengerSponge,
+    SierpinskiTetrahedron,
+    Mandelbox,
 
     // Binary operators
     Union,
     Subtraction,
-    // Intersection,
+    Intersection,
+    SmoothUnion,
+    SmoothSubtraction,
+    SmoothIntersection,
+    Morph,
+    ChamferUnion,
+    StairsUnion,
+    StairsSubtraction,
+    ColumnsUnion,
+    Groove,
+    Tongue,
+    Xor,
 
     // Space transformations
-    // Translation,
-    // Rotation,
-    // Scale,
+    Translation,
+    Rotation,
+    Scale,
+    Repeat,
+    Mirror,
+    RadialSymmetry,
+
+    // Deformations
+    Twist,
+    Bend,
+    Taper,
+    SineDisplace,
+    Displace,
+
+    // Modifiers
+    Onion,
+    Offset,
+    Elongate,
+
+    // 2D profiles
+    Extrude,
+    Revolve,
 }
 
 #[enum_dispatch(CSGNodeTemplateTrait)]
@@ -49,8 +122,52 @@ pub enum CSGNode {
 pub enum CSGNodeTemplate {
     Sphere(SphereTemplate),
     Box(BoxTemplate),
+    Plane(PlaneTemplate),
+    Torus(TorusTemplate),
+    Cylinder(CylinderTemplate),
+    Capsule(CapsuleTemplate),
+    Cone(ConeTemplate),
+    Ellipsoid(EllipsoidTemplate),
+    RoundedBox(RoundedBoxTemplate),
+    Octahedron(OctahedronTemplate),
+    Polyline(PolylineTemplate),
+    QuadraticBezier(QuadraticBezierTemplate),
+    CubicBezier(CubicBezierTemplate),
+    GridSdf(GridSdfTemplate),
+    Mandelbulb(MandelbulbTemplate),
+    MengerSponge(MengerSpongeTemplate),
+    SierpinskiTetrahedron(SierpinskiTetrahedronTemplate),
+    Mandelbox(MandelboxTemplate),
     Union(UnionTemplate),
     Subtraction(SubtractionTemplate),
+    Intersection(IntersectionTemplate),
+    SmoothUnion(SmoothUnionTemplate),
+    SmoothSubtraction(SmoothSubtractionTemplate),
+    SmoothIntersection(SmoothIntersectionTemplate),
+    Morph(MorphTemplate),
+    ChamferUnion(ChamferUnionTemplate),
+    StairsUnion(StairsUnionTemplate),
+    StairsSubtraction(StairsSubtractionTemplate),
+    ColumnsUnion(ColumnsUnionTemplate),
+    Groove(GrooveTemplate),
+    Tongue(TongueTemplate),
+    Xor(XorTemplate),
+    Translation(TranslationTemplate),
+    Rotation(RotationTemplate),
+    Scale(ScaleTemplate),
+    Repeat(RepeatTemplate),
+    Mirror(MirrorTemplate),
+    RadialSymmetry(RadialSymmetryTemplate),
+    Twist(TwistTemplate),
+    Bend(BendTemplate),
+    Taper(TaperTemplate),
+    SineDisplace(SineDisplaceTemplate),
+    Displace(DisplaceTemplate),
+    Onion(OnionTemplate),
+    Offset(OffsetTemplate),
+    Elongate(ElongateTemplate),
+    Extrude(ExtrudeTemplate),
+    Revolve(RevolveTemplate),
 }
 
 impl CSGNodeTemplate {
@@ -58,8 +175,52 @@ impl CSGNodeTemplate {
         [
             CSGNodeTemplate::Sphere(SphereTemplate),
             CSGNodeTemplate::Box(BoxTemplate),
+            CSGNodeTemplate::Plane(PlaneTemplate),
+            CSGNodeTemplate::Torus(TorusTemplate),
+            CSGNodeTemplate::Cylinder(CylinderTemplate),
+            CSGNodeTemplate::Capsule(CapsuleTemplate),
+            CSGNodeTemplate::Cone(ConeTemplate),
+            CSGNodeTemplate::Ellipsoid(EllipsoidTemplate),
+            CSGNodeTemplate::RoundedBox(RoundedBoxTemplate),
+            CSGNodeTemplate::Octahedron(OctahedronTemplate),
+            CSGNodeTemplate::Polyline(PolylineTemplate),
+            CSGNodeTemplate::QuadraticBezier(QuadraticBezierTemplate),
+            CSGNodeTemplate::CubicBezier(CubicBezierTemplate),
+            CSGNodeTemplate::GridSdf(GridSdfTemplate),
+            CSGNodeTemplate::Mandelbulb(MandelbulbTemplate),
+            CSGNodeTemplate::MengerSponge(MengerSpongeTemplate),
+            CSGNodeTemplate::SierpinskiTetrahedron(SierpinskiTetrahedronTemplate),
+            CSGNodeTemplate::Mandelbox(MandelboxTemplate),
             CSGNodeTemplate::Union(UnionTemplate),
             CSGNodeTemplate::Subtraction(SubtractionTemplate),
+            CSGNodeTemplate::Intersection(IntersectionTemplate),
+            CSGNodeTemplate::SmoothUnion(SmoothUnionTemplate),
+            CSGNodeTemplate::SmoothSubtraction(SmoothSubtractionTemplate),
+            CSGNodeTemplate::SmoothIntersection(SmoothIntersectionTemplate),
+            CSGNodeTemplate::Morph(MorphTemplate),
+            CSGNodeTemplate::ChamferUnion(ChamferUnionTemplate),
+            CSGNodeTemplate::StairsUnion(StairsUnionTemplate),
+            CSGNodeTemplate::StairsSubtraction(StairsSubtractionTemplate),
+            CSGNodeTemplate::ColumnsUnion(ColumnsUnionTemplate),
+            CSGNodeTemplate::Groove(GrooveTemplate),
+            CSGNodeTemplate::Tongue(TongueTemplate),
+            CSGNodeTemplate::Xor(XorTemplate),
+            CSGNodeTemplate::Translation(TranslationTemplate),
+            CSGNodeTemplate::Rotation(RotationTemplate),
+            CSGNodeTemplate::Scale(ScaleTemplate),
+            CSGNodeTemplate::Repeat(RepeatTemplate),
+            CSGNodeTemplate::Mirror(MirrorTemplate),
+            CSGNodeTemplate::RadialSymmetry(RadialSymmetryTemplate),
+            CSGNodeTemplate::Twist(TwistTemplate),
+            CSGNodeTemplate::Bend(BendTemplate),
+            CSGNodeTemplate::Taper(TaperTemplate),
+            CSGNodeTemplate::SineDisplace(SineDisplaceTemplate),
+            CSGNodeTemplate::Displace(DisplaceTemplate),
+            CSGNodeTemplate::Onion(OnionTemplate),
+            CSGNodeTemplate::Offset(OffsetTemplate),
+            CSGNodeTemplate::Elongate(ElongateTemplate),
+            CSGNodeTemplate::Extrude(ExtrudeTemplate),
+            CSGNodeTemplate::Revolve(RevolveTemplate),
         ]
     }
 }
@@ -0,0 +1,66 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Stretches the child by splitting it at the axis planes and moving the halves apart.
+#[derive(Debug, Clone)]
+pub struct Elongate {
+    /// Distance each half is moved along each axis.
+    pub(crate) length: [f32; 3],
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl BuildCommands for Elongate {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::ElongatePush)
+            .push_param_vec3(self.length.map(|length| length.max(0.)));
+        self.child.build_commands(builder);
+        builder.push_command(CSGCommandType::ElongatePop);
+    }
+}
+
+impl SignedDistance for Elongate {
+    fn distance_kind(&self) -> DistanceKind {
+        // Like a union, elongation is only exact outside of the shape.
+        self.child.distance_kind()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ElongateTemplate;
+impl CSGNodeTemplateTrait for ElongateTemplate {
+    fn name(&self) -> &'static str {
+        "Elongate"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "length",
+                ValueType::Vec3([1., 0., 0.]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let length = input_params.vec3("length")?;
+        Ok(Elongate {
+            length,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
@@ -0,0 +1,89 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+mod elongate;
+
+pub use elongate::*;
+
+/// Implements a modifier that changes the distance of a single SDF by a scalar parameter.
+macro_rules! impl_distance_modifier {
+    ($name:ident, $template_name:ident, $command:ident, $param:ident = $default:expr) => {
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            pub(crate) $param: f32,
+            pub(crate) child: Box<CSGNode>,
+        }
+
+        impl BuildCommands for $name {
+            fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+                self.child.build_commands(builder);
+                builder
+                    .push_command(CSGCommandType::$command)
+                    .push_param_float(self.$param);
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct $template_name;
+
+        impl CSGNodeTemplateTrait for $template_name {
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+                vec![
+                    (
+                        "SDF",
+                        ValueType::CSGNode(None),
+                        InputParamKind::ConnectionOnly,
+                    ),
+                    (
+                        stringify!($param),
+                        ValueType::Scalar($default),
+                        InputParamKind::ConnectionOrConstant,
+                    ),
+                ]
+            }
+
+            fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+                let child = input_params.csg_node("SDF")?;
+                let $param = input_params.scalar(stringify!($param))?;
+                Ok($name {
+                    $param,
+                    child: Box::new(child),
+                }
+                .into())
+            }
+        }
+    };
+}
+
+impl_distance_modifier!(Onion, OnionTemplate, Onion, thickness = 0.1);
+// A positive offset also rounds the edges of the shape with a radius of the offset. To round a shape
+// without growing it, shrink the child by the offset, e.g. the size of a box.
+impl_distance_modifier!(Offset, OffsetTemplate, Offset, offset = 0.);
+
+impl SignedDistance for Onion {
+    fn distance_kind(&self) -> DistanceKind {
+        self.child.distance_kind()
+    }
+}
+
+// Growing a shape keeps exact distances exact outside of it. The surface of a shrunk shape can be
+// further away than the child's distance minus the offset, which makes the distance a bound.
+impl SignedDistance for Offset {
+    fn distance_kind(&self) -> DistanceKind {
+        if self.offset >= 0. {
+            self.child.distance_kind()
+        } else {
+            DistanceKind::Bound
+        }
+    }
+}
@@ -1,19 +1,40 @@
 use egui_node_graph::InputParamKind;
 
 use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
 use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
-use crate::ray_marching::csg::{BuildCommands, CSGNode, CSGNodeTemplateTrait};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
 
+mod variadic;
+
+pub use variadic::*;
+
+/// Implements a binary operation on two SDFs.
+/// Optional scalar parameters (`name = default`) are pushed after the command, a parameter can be
+/// sanitized by a function when evaluating (`name = default => function`).
 macro_rules! impl_binary_operation {
-    ($name:ident, $template_name:ident, $command:ident) => {
+    (
+        $name:ident,
+        $template_name:ident,
+        $command:ident
+        $(, $param:ident = $default:expr $(=> $sanitize:expr)?)*
+    ) => {
         #[derive(Debug, Clone)]
-        pub struct $name(pub Box<CSGNode>, pub Box<CSGNode>);
+        pub struct $name {
+            pub(crate) a: Box<CSGNode>,
+            pub(crate) b: Box<CSGNode>,
+            $(pub(crate) $param: f32,)*
+        }
 
         impl BuildCommands for $name {
             fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
-                self.0.build_commands(builder);
-                self.1.build_commands(builder);
-                builder.push_command(CSGCommandType::$command);
+                self.a.build_commands(builder);
+                self.b.build_commands(builder);
+                builder
+                    .push_command(CSGCommandType::$command)
+                    $(.push_param_float(self.$param))*;
             }
         }
 
@@ -37,20 +58,112 @@ macro_rules! impl_binary_operation {
                         ValueType::CSGNode(None),
                         InputParamKind::ConnectionOnly,
                     ),
+                    $((
+                        stringify!($param),
+                        ValueType::Scalar($default),
+                        InputParamKind::ConnectionOrConstant,
+                    ),)*
                 ]
             }
 
             fn evaluate(
                 &self,
-                input_params: std::collections::HashMap<String, ValueType>,
-            ) -> Option<CSGNode> {
-                let lhs = input_params.get("A").unwrap().to_csg_node()?;
-                let rhs = input_params.get("B").unwrap().to_csg_node()?;
-                Some($name(Box::new(lhs), Box::new(rhs)).into())
+                input_params: &InputParams,
+            ) -> Result<CSGNode, Diagnostic> {
+                let a = input_params.csg_node("A")?;
+                let b = input_params.csg_node("B")?;
+                $(
+                    let $param = input_params.scalar(stringify!($param))?;
+                    $(let $param = $sanitize($param);)?
+                )*
+                Ok(
+                    $name {
+                        a: Box::new(a),
+                        b: Box::new(b),
+                        $($param,)*
+                    }
+                    .into(),
+                )
             }
         }
     };
 }
 
-impl_binary_operation!(Union, UnionTemplate, Union);
 impl_binary_operation!(Subtraction, SubtractionTemplate, Subtraction);
+impl_binary_operation!(
+    SmoothSubtraction,
+    SmoothSubtractionTemplate,
+    SmoothSubtraction,
+    k = 0.5
+);
+impl_binary_operation!(
+    SmoothIntersection,
+    SmoothIntersectionTemplate,
+    SmoothIntersection,
+    k = 0.5
+);
+impl_binary_operation!(Morph, MorphTemplate, Morph, t = 0.5);
+
+/// Counts such as steps and columns are whole numbers of at least one, as the shader divides by
+/// them.
+fn count(value: f32) -> f32 {
+    value.round().max(1.)
+}
+
+// Decorative combinations from hg_sdf, see https://mercury.sexy/hg_sdf/
+impl_binary_operation!(
+    ChamferUnion,
+    ChamferUnionTemplate,
+    ChamferUnion,
+    radius = 0.3
+);
+impl_binary_operation!(
+    StairsUnion,
+    StairsUnionTemplate,
+    StairsUnion,
+    radius = 0.3,
+    steps = 4.0 => count
+);
+impl_binary_operation!(
+    StairsSubtraction,
+    StairsSubtractionTemplate,
+    StairsSubtraction,
+    radius = 0.3,
+    steps = 4.0 => count
+);
+impl_binary_operation!(
+    ColumnsUnion,
+    ColumnsUnionTemplate,
+    ColumnsUnion,
+    radius = 0.3,
+    columns = 4.0 => count
+);
+impl_binary_operation!(Groove, GrooveTemplate, Groove, depth = 0.1, width = 0.1);
+impl_binary_operation!(Tongue, TongueTemplate, Tongue, height = 0.1, width = 0.1);
+impl_binary_operation!(Xor, XorTemplate, Xor);
+
+macro_rules! impl_bound_distance {
+    ($($name:ident),*) => {
+        $(
+            impl SignedDistance for $name {
+                fn distance_kind(&self) -> DistanceKind {
+                    DistanceKind::Bound
+                }
+            }
+        )*
+    };
+}
+
+impl_bound_distance!(
+    Subtraction,
+    SmoothSubtraction,
+    SmoothIntersection,
+    Morph,
+    ChamferUnion,
+    StairsUnion,
+    StairsSubtraction,
+    ColumnsUnion,
+    Groove,
+    Tongue,
+    Xor
+);
@@ -0,0 +1,121 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Names of the variadic inputs of nodes, in order.
+/// Variadic nodes start out with the first two, and can grow up to all of them.
+pub const VARIADIC_INPUT_NAMES: [&str; 16] = [
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P",
+];
+
+/// Implements an operation on any number of SDFs, whose command folds all of them in the shader.
+/// Optional scalar parameters (`name = default`) are pushed after the number of children.
+macro_rules! impl_variadic_operation {
+    ($name:ident, $template_name:ident, $command:ident $(, $param:ident = $default:expr)*) => {
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            pub(crate) children: Vec<CSGNode>,
+            $(pub(crate) $param: f32,)*
+        }
+
+        impl BuildCommands for $name {
+            fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+                for child in &self.children {
+                    child.build_commands(builder);
+                }
+                // A single child is the result as is.
+                if self.children.len() > 1 {
+                    builder
+                        .push_command(CSGCommandType::$command)
+                        .push_param_child_count(self.children.len() as u32)
+                        $(.push_param_float(self.$param))*;
+                }
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        pub struct $template_name;
+
+        impl CSGNodeTemplateTrait for $template_name {
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+                vec![
+                    (
+                        VARIADIC_INPUT_NAMES[0],
+                        ValueType::CSGNode(None),
+                        InputParamKind::ConnectionOnly,
+                    ),
+                    (
+                        VARIADIC_INPUT_NAMES[1],
+                        ValueType::CSGNode(None),
+                        InputParamKind::ConnectionOnly,
+                    ),
+                    $((
+                        stringify!($param),
+                        ValueType::Scalar($default),
+                        InputParamKind::ConnectionOrConstant,
+                    ),)*
+                ]
+            }
+
+            fn is_variadic(&self) -> bool {
+                true
+            }
+
+            fn evaluate(
+                &self,
+                input_params: &InputParams,
+            ) -> Result<CSGNode, Diagnostic> {
+                // Unconnected inputs are skipped, so sockets can be added before connecting them.
+                let children: Vec<_> = VARIADIC_INPUT_NAMES
+                    .iter()
+                    .filter(|name| input_params.contains(name))
+                    .filter_map(|name| input_params.optional_csg_node(name).transpose())
+                    .collect::<Result<_, _>>()?;
+                if children.is_empty() {
+                    return Err(Diagnostic::MissingInput(VARIADIC_INPUT_NAMES[0].to_string()));
+                }
+                $(let $param = input_params.scalar(stringify!($param))?;)*
+                Ok(
+                    $name {
+                        children,
+                        $($param,)*
+                    }
+                    .into(),
+                )
+            }
+        }
+    };
+}
+
+impl_variadic_operation!(Union, UnionTemplate, Union);
+impl_variadic_operation!(Intersection, IntersectionTemplate, Intersection);
+impl_variadic_operation!(SmoothUnion, SmoothUnionTemplate, SmoothUnion, k = 0.5);
+
+impl SignedDistance for Union {
+    fn distance_kind(&self) -> DistanceKind {
+        // Even a union of exact SDFs is only exact outside the shape, inside it underestimates the
+        // distance to the surface where children overlap.
+        DistanceKind::Bound
+    }
+}
+
+impl SignedDistance for Intersection {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+impl SignedDistance for SmoothUnion {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
@@ -0,0 +1,64 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Capsule along the Y axis.
+#[derive(Debug, Clone)]
+pub struct Capsule {
+    pub(crate) radius: f32,
+    /// Half the length of the line segment between the two hemispheres.
+    pub(crate) half_height: f32,
+}
+
+impl BuildCommands for Capsule {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Capsule)
+            .push_param_float(self.radius)
+            .push_param_float(self.half_height);
+    }
+}
+
+impl SignedDistance for Capsule {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CapsuleTemplate;
+impl CSGNodeTemplateTrait for CapsuleTemplate {
+    fn name(&self) -> &'static str {
+        "Capsule"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "radius",
+                ValueType::Scalar(0.5),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "half_height",
+                ValueType::Scalar(0.5),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let radius = input_params.scalar("radius")?;
+        let half_height = input_params.scalar("half_height")?;
+        Ok(Capsule {
+            radius,
+            half_height,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,72 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Capped cone along the Y axis.
+#[derive(Debug, Clone)]
+pub struct Cone {
+    pub(crate) half_height: f32,
+    pub(crate) bottom_radius: f32,
+    pub(crate) top_radius: f32,
+}
+
+impl BuildCommands for Cone {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Cone)
+            .push_param_float(self.half_height)
+            .push_param_float(self.bottom_radius)
+            .push_param_float(self.top_radius);
+    }
+}
+
+impl SignedDistance for Cone {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ConeTemplate;
+impl CSGNodeTemplateTrait for ConeTemplate {
+    fn name(&self) -> &'static str {
+        "Cone"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "half_height",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "bottom_radius",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "top_radius",
+                ValueType::Scalar(0.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let half_height = input_params.scalar("half_height")?;
+        let bottom_radius = input_params.scalar("bottom_radius")?;
+        let top_radius = input_params.scalar("top_radius")?;
+        Ok(Cone {
+            half_height,
+            bottom_radius,
+            top_radius,
+        }
+        .into())
+    }
+}
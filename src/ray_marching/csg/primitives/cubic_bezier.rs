@@ -0,0 +1,92 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Tube of constant radius swept along a cubic Bezier curve.
+#[derive(Debug, Clone)]
+pub struct CubicBezier {
+    pub(crate) start: [f32; 3],
+    pub(crate) control_1: [f32; 3],
+    pub(crate) control_2: [f32; 3],
+    pub(crate) end: [f32; 3],
+    pub(crate) radius: f32,
+}
+
+impl BuildCommands for CubicBezier {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::CubicBezier)
+            .push_param_vec3(self.start)
+            .push_param_vec3(self.control_1)
+            .push_param_vec3(self.control_2)
+            .push_param_vec3(self.end)
+            .push_param_float(self.radius);
+    }
+}
+
+impl SignedDistance for CubicBezier {
+    fn distance_kind(&self) -> DistanceKind {
+        // The shader measures the distance to line segments approximating the curve, less the
+        // largest distance between them and the curve.
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CubicBezierTemplate;
+impl CSGNodeTemplateTrait for CubicBezierTemplate {
+    fn name(&self) -> &'static str {
+        "CubicBezier"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "start",
+                ValueType::Vec3([-1.5, 0.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "control_1",
+                ValueType::Vec3([-0.5, 2.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "control_2",
+                ValueType::Vec3([0.5, -2.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "end",
+                ValueType::Vec3([1.5, 0.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "radius",
+                ValueType::Scalar(0.25),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let start = input_params.vec3("start")?;
+        let control_1 = input_params.vec3("control_1")?;
+        let control_2 = input_params.vec3("control_2")?;
+        let end = input_params.vec3("end")?;
+        let radius = input_params.scalar("radius")?;
+        Ok(CubicBezier {
+            start,
+            control_1,
+            control_2,
+            end,
+            radius,
+        }
+        .into())
+    }
+}
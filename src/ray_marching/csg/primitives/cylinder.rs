@@ -0,0 +1,63 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Capped cylinder along the Y axis.
+#[derive(Debug, Clone)]
+pub struct Cylinder {
+    pub(crate) radius: f32,
+    pub(crate) half_height: f32,
+}
+
+impl BuildCommands for Cylinder {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Cylinder)
+            .push_param_float(self.radius)
+            .push_param_float(self.half_height);
+    }
+}
+
+impl SignedDistance for Cylinder {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CylinderTemplate;
+impl CSGNodeTemplateTrait for CylinderTemplate {
+    fn name(&self) -> &'static str {
+        "Cylinder"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "radius",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "half_height",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let radius = input_params.scalar("radius")?;
+        let half_height = input_params.scalar("half_height")?;
+        Ok(Cylinder {
+            radius,
+            half_height,
+        }
+        .into())
+    }
+}
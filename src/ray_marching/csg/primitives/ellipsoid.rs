@@ -0,0 +1,48 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct Ellipsoid {
+    pub(crate) radius: [f32; 3],
+}
+
+impl BuildCommands for Ellipsoid {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Ellipsoid)
+            .push_param_vec3(self.radius);
+    }
+}
+
+impl SignedDistance for Ellipsoid {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct EllipsoidTemplate;
+impl CSGNodeTemplateTrait for EllipsoidTemplate {
+    fn name(&self) -> &'static str {
+        "Ellipsoid"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![(
+            "radius",
+            ValueType::Vec3([1., 0.5, 0.5]),
+            InputParamKind::ConnectionOrConstant,
+        )]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let radius = input_params.vec3("radius")?;
+        Ok(Ellipsoid { radius }.into())
+    }
+}
@@ -0,0 +1,42 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Signed distances sampled from the grid baked from an imported mesh, see
+/// [`SdfGrid`](crate::ray_marching::sdf_grid::SdfGrid).
+#[derive(Debug, Clone)]
+pub struct GridSdf;
+
+impl BuildCommands for GridSdf {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder.push_command(CSGCommandType::GridSdf);
+    }
+}
+
+impl SignedDistance for GridSdf {
+    fn distance_kind(&self) -> DistanceKind {
+        // The samples are interpolated, and outside the grid only the distance to it is known.
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct GridSdfTemplate;
+impl CSGNodeTemplateTrait for GridSdfTemplate {
+    fn name(&self) -> &'static str {
+        "GridSdf"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![]
+    }
+
+    fn evaluate(&self, _input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        Ok(GridSdf.into())
+    }
+}
@@ -1,5 +1,29 @@
 mod r#box;
+mod capsule;
+mod cone;
+mod cubic_bezier;
+mod cylinder;
+mod ellipsoid;
+mod grid_sdf;
+mod octahedron;
+mod plane;
+mod polyline;
+mod quadratic_bezier;
+mod rounded_box;
 mod sphere;
+mod torus;
 
+pub use capsule::*;
+pub use cone::*;
+pub use cubic_bezier::*;
+pub use cylinder::*;
+pub use ellipsoid::*;
+pub use grid_sdf::*;
+pub use octahedron::*;
+pub use plane::*;
+pub use polyline::*;
+pub use quadratic_bezier::*;
 pub use r#box::*;
+pub use rounded_box::*;
 pub use sphere::*;
+pub use torus::*;
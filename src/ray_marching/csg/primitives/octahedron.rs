@@ -0,0 +1,48 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct Octahedron {
+    pub(crate) size: f32,
+}
+
+impl BuildCommands for Octahedron {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Octahedron)
+            .push_param_float(self.size);
+    }
+}
+
+impl SignedDistance for Octahedron {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct OctahedronTemplate;
+impl CSGNodeTemplateTrait for OctahedronTemplate {
+    fn name(&self) -> &'static str {
+        "Octahedron"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![(
+            "size",
+            ValueType::Scalar(1.),
+            InputParamKind::ConnectionOrConstant,
+        )]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let size = input_params.scalar("size")?;
+        Ok(Octahedron { size }.into())
+    }
+}
@@ -0,0 +1,64 @@
+use egui_node_graph::InputParamKind;
+use nalgebra::Vector3;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct Plane {
+    pub(crate) normal: [f32; 3],
+    pub(crate) offset: f32,
+}
+
+impl BuildCommands for Plane {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        // A zero normal can't be normalized, fall back to the default up-facing plane.
+        let normal = Vector3::from(self.normal)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+
+        builder
+            .push_command(CSGCommandType::Plane)
+            .push_param_vec3(normal.into())
+            .push_param_float(self.offset);
+    }
+}
+
+impl SignedDistance for Plane {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PlaneTemplate;
+impl CSGNodeTemplateTrait for PlaneTemplate {
+    fn name(&self) -> &'static str {
+        "Plane"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "normal",
+                ValueType::Vec3([0., 1., 0.]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "offset",
+                ValueType::Scalar(0.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let normal = input_params.vec3("normal")?;
+        let offset = input_params.scalar("offset")?;
+        Ok(Plane { normal, offset }.into())
+    }
+}
@@ -0,0 +1,73 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+    VARIADIC_INPUT_NAMES,
+};
+
+/// Tube of constant radius swept along the line segments between consecutive points.
+#[derive(Debug, Clone)]
+pub struct Polyline {
+    pub(crate) points: Vec<[f32; 3]>,
+    pub(crate) radius: f32,
+}
+
+impl BuildCommands for Polyline {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Polyline)
+            .push_param_vec3_list(&self.points)
+            .push_param_float(self.radius);
+    }
+}
+
+impl SignedDistance for Polyline {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PolylineTemplate;
+impl CSGNodeTemplateTrait for PolylineTemplate {
+    fn name(&self) -> &'static str {
+        "Polyline"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                VARIADIC_INPUT_NAMES[0],
+                ValueType::Vec3([-1.0, 0.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                VARIADIC_INPUT_NAMES[1],
+                ValueType::Vec3([1.0, 0.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "radius",
+                ValueType::Scalar(0.25),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn is_variadic(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let points = VARIADIC_INPUT_NAMES
+            .iter()
+            .filter(|name| input_params.contains(name))
+            .map(|name| input_params.vec3(name))
+            .collect::<Result<_, _>>()?;
+        let radius = input_params.scalar("radius")?;
+        Ok(Polyline { points, radius }.into())
+    }
+}
@@ -0,0 +1,81 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Tube of constant radius swept along a quadratic Bezier curve.
+#[derive(Debug, Clone)]
+pub struct QuadraticBezier {
+    pub(crate) start: [f32; 3],
+    pub(crate) control: [f32; 3],
+    pub(crate) end: [f32; 3],
+    pub(crate) radius: f32,
+}
+
+impl BuildCommands for QuadraticBezier {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::QuadraticBezier)
+            .push_param_vec3(self.start)
+            .push_param_vec3(self.control)
+            .push_param_vec3(self.end)
+            .push_param_float(self.radius);
+    }
+}
+
+impl SignedDistance for QuadraticBezier {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct QuadraticBezierTemplate;
+impl CSGNodeTemplateTrait for QuadraticBezierTemplate {
+    fn name(&self) -> &'static str {
+        "QuadraticBezier"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "start",
+                ValueType::Vec3([-1.0, 0.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "control",
+                ValueType::Vec3([0.0, 2.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "end",
+                ValueType::Vec3([1.0, 0.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "radius",
+                ValueType::Scalar(0.25),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let start = input_params.vec3("start")?;
+        let control = input_params.vec3("control")?;
+        let end = input_params.vec3("end")?;
+        let radius = input_params.scalar("radius")?;
+        Ok(QuadraticBezier {
+            start,
+            control,
+            end,
+            radius,
+        }
+        .into())
+    }
+}
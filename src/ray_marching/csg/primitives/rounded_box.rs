@@ -0,0 +1,58 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct RoundedBox {
+    pub(crate) radius: [f32; 3],
+    pub(crate) rounding: f32,
+}
+
+impl BuildCommands for RoundedBox {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::RoundedBox)
+            .push_param_vec3(self.radius)
+            .push_param_float(self.rounding);
+    }
+}
+
+impl SignedDistance for RoundedBox {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RoundedBoxTemplate;
+impl CSGNodeTemplateTrait for RoundedBoxTemplate {
+    fn name(&self) -> &'static str {
+        "RoundedBox"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "radius",
+                ValueType::Vec3([1.; 3]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "rounding",
+                ValueType::Scalar(0.1),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let radius = input_params.vec3("radius")?;
+        let rounding = input_params.scalar("rounding")?;
+        Ok(RoundedBox { radius, rounding }.into())
+    }
+}
@@ -1,14 +1,14 @@
 use egui_node_graph::InputParamKind;
-use std::collections::HashMap;
 
 use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
 use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
-use crate::ray_marching::csg::{BuildCommands, CSGNode, CSGNodeTemplateTrait};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
 
 #[derive(Debug, Clone)]
 pub struct Sphere {
-    // TODO: Remove center in favor of just adding a Translation node
-    pub(crate) center: [f32; 3],
     pub(crate) radius: f32,
 }
 
@@ -16,11 +16,16 @@ impl BuildCommands for Sphere {
     fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
         builder
             .push_command(CSGCommandType::Sphere)
-            .push_param_vec3(self.center)
             .push_param_float(self.radius);
     }
 }
 
+impl SignedDistance for Sphere {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct SphereTemplate;
 impl CSGNodeTemplateTrait for SphereTemplate {
@@ -29,23 +34,15 @@ impl CSGNodeTemplateTrait for SphereTemplate {
     }
 
     fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
-        vec![
-            (
-                "center",
-                ValueType::Vec3([0.; 3]),
-                InputParamKind::ConnectionOrConstant,
-            ),
-            (
-                "radius",
-                ValueType::Scalar(1.),
-                InputParamKind::ConnectionOrConstant,
-            ),
-        ]
+        vec![(
+            "radius",
+            ValueType::Scalar(1.),
+            InputParamKind::ConnectionOrConstant,
+        )]
     }
 
-    fn evaluate(&self, input_params: HashMap<String, ValueType>) -> Option<CSGNode> {
-        let center = input_params.get("center").unwrap().to_vec3().unwrap();
-        let radius = input_params.get("radius").unwrap().to_scalar().unwrap();
-        Some(Sphere { center, radius }.into())
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let radius = input_params.scalar("radius")?;
+        Ok(Sphere { radius }.into())
     }
 }
@@ -0,0 +1,63 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Torus lying in the XZ plane.
+#[derive(Debug, Clone)]
+pub struct Torus {
+    pub(crate) major_radius: f32,
+    pub(crate) minor_radius: f32,
+}
+
+impl BuildCommands for Torus {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Torus)
+            .push_param_float(self.major_radius)
+            .push_param_float(self.minor_radius);
+    }
+}
+
+impl SignedDistance for Torus {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TorusTemplate;
+impl CSGNodeTemplateTrait for TorusTemplate {
+    fn name(&self) -> &'static str {
+        "Torus"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "major_radius",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "minor_radius",
+                ValueType::Scalar(0.25),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let major_radius = input_params.scalar("major_radius")?;
+        let minor_radius = input_params.scalar("minor_radius")?;
+        Ok(Torus {
+            major_radius,
+            minor_radius,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,48 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, DistanceKind, SDF2DNode, SDF2DNodeTemplateTrait, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct Circle {
+    pub(crate) radius: f32,
+}
+
+impl BuildCommands for Circle {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Circle)
+            .push_param_float(self.radius);
+    }
+}
+
+impl SignedDistance for Circle {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CircleTemplate;
+impl SDF2DNodeTemplateTrait for CircleTemplate {
+    fn name(&self) -> &'static str {
+        "Circle"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![(
+            "radius",
+            ValueType::Scalar(1.),
+            InputParamKind::ConnectionOrConstant,
+        )]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<SDF2DNode, Diagnostic> {
+        let radius = input_params.scalar("radius")?;
+        Ok(Circle { radius }.into())
+    }
+}
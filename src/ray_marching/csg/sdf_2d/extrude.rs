@@ -0,0 +1,64 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SDF2DNode, SignedDistance,
+};
+
+/// Extrudes a 2D profile on the XY plane along the Z axis.
+#[derive(Debug, Clone)]
+pub struct Extrude {
+    pub(crate) half_depth: f32,
+    pub(crate) profile: Box<SDF2DNode>,
+}
+
+impl BuildCommands for Extrude {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder.push_command(CSGCommandType::ExtrudePush);
+        self.profile.build_commands(builder);
+        builder
+            .push_command(CSGCommandType::ExtrudePop)
+            .push_param_float(self.half_depth);
+    }
+}
+
+impl SignedDistance for Extrude {
+    fn distance_kind(&self) -> DistanceKind {
+        self.profile.distance_kind()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ExtrudeTemplate;
+impl CSGNodeTemplateTrait for ExtrudeTemplate {
+    fn name(&self) -> &'static str {
+        "Extrude"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "profile",
+                ValueType::SDF2D(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "half_depth",
+                ValueType::Scalar(0.5),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let profile = input_params.sdf_2d("profile")?;
+        let half_depth = input_params.scalar("half_depth")?;
+        Ok(Extrude {
+            half_depth,
+            profile: Box::new(profile),
+        }
+        .into())
+    }
+}
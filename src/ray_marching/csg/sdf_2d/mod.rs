@@ -0,0 +1,71 @@
+use egui_node_graph::InputParamKind;
+use enum_dispatch::enum_dispatch;
+
+pub use circle::*;
+pub use extrude::*;
+pub use polygon::*;
+pub use rectangle::*;
+pub use regular_polygon::*;
+pub use revolve::*;
+pub use rounded_rectangle::*;
+pub use text::*;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+
+mod circle;
+mod extrude;
+mod polygon;
+mod rectangle;
+mod regular_polygon;
+mod revolve;
+mod rounded_rectangle;
+mod text;
+
+#[enum_dispatch]
+pub trait SDF2DNodeTemplateTrait {
+    fn name(&self) -> &'static str;
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)>;
+    /// Whether the user can add and remove copies of the first input, see
+    /// [`super::CSGNodeTemplateTrait::is_variadic`].
+    fn is_variadic(&self) -> bool {
+        false
+    }
+    fn evaluate(&self, input_params: &InputParams) -> Result<SDF2DNode, Diagnostic>;
+}
+
+/// 2D SDF, evaluated on the XY plane of the space set up by [`Extrude`] or [`Revolve`].
+#[enum_dispatch(BuildCommands, SignedDistance)]
+#[derive(Debug, Clone)]
+pub enum SDF2DNode {
+    Circle,
+    Rectangle,
+    RegularPolygon,
+    RoundedRectangle,
+    Text,
+    Polygon,
+}
+
+#[enum_dispatch(SDF2DNodeTemplateTrait)]
+#[derive(Debug, Clone)]
+pub enum SDF2DNodeTemplate {
+    Circle(CircleTemplate),
+    Rectangle(RectangleTemplate),
+    RegularPolygon(RegularPolygonTemplate),
+    RoundedRectangle(RoundedRectangleTemplate),
+    Text(TextTemplate),
+    Polygon(PolygonTemplate),
+}
+
+impl SDF2DNodeTemplate {
+    pub fn all() -> impl IntoIterator<Item = Self> {
+        [
+            SDF2DNodeTemplate::Circle(CircleTemplate),
+            SDF2DNodeTemplate::Rectangle(RectangleTemplate),
+            SDF2DNodeTemplate::RegularPolygon(RegularPolygonTemplate),
+            SDF2DNodeTemplate::Polygon(PolygonTemplate),
+            SDF2DNodeTemplate::RoundedRectangle(RoundedRectangleTemplate),
+            SDF2DNodeTemplate::Text(TextTemplate),
+        ]
+    }
+}
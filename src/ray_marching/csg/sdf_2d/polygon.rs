@@ -0,0 +1,72 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, DistanceKind, SDF2DNode, SDF2DNodeTemplateTrait, SignedDistance,
+    VARIADIC_INPUT_NAMES,
+};
+
+/// Polygon with arbitrary vertices, closed by an edge from the last vertex back to the first.
+/// Self-intersecting polygons are filled by the even-odd rule.
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    pub(crate) vertices: Vec<[f32; 2]>,
+}
+
+impl BuildCommands for Polygon {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Polygon)
+            .push_param_vec2_list(&self.vertices);
+    }
+}
+
+impl SignedDistance for Polygon {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct PolygonTemplate;
+impl SDF2DNodeTemplateTrait for PolygonTemplate {
+    fn name(&self) -> &'static str {
+        "Polygon"
+    }
+
+    // There is no 2D vector type, the vertices are Vec3 inputs whose Z is ignored.
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                VARIADIC_INPUT_NAMES[0],
+                ValueType::Vec3([0.0, 1.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                VARIADIC_INPUT_NAMES[1],
+                ValueType::Vec3([-1.0, -1.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                VARIADIC_INPUT_NAMES[2],
+                ValueType::Vec3([1.0, -1.0, 0.0]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn is_variadic(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<SDF2DNode, Diagnostic> {
+        let vertices = VARIADIC_INPUT_NAMES
+            .iter()
+            .filter(|name| input_params.contains(name))
+            .map(|name| input_params.vec3(name).map(|[x, y, _]| [x, y]))
+            .collect::<Result<_, _>>()?;
+        Ok(Polygon { vertices }.into())
+    }
+}
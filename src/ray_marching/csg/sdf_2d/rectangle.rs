@@ -0,0 +1,62 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, DistanceKind, SDF2DNode, SDF2DNodeTemplateTrait, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct Rectangle {
+    pub(crate) half_width: f32,
+    pub(crate) half_height: f32,
+}
+
+impl BuildCommands for Rectangle {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Rectangle)
+            .push_param_float(self.half_width)
+            .push_param_float(self.half_height);
+    }
+}
+
+impl SignedDistance for Rectangle {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RectangleTemplate;
+impl SDF2DNodeTemplateTrait for RectangleTemplate {
+    fn name(&self) -> &'static str {
+        "Rectangle"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "half_width",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "half_height",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<SDF2DNode, Diagnostic> {
+        let half_width = input_params.scalar("half_width")?;
+        let half_height = input_params.scalar("half_height")?;
+        Ok(Rectangle {
+            half_width,
+            half_height,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,63 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, DistanceKind, SDF2DNode, SDF2DNodeTemplateTrait, SignedDistance,
+};
+
+/// Regular polygon with a corner on the positive Y axis.
+#[derive(Debug, Clone)]
+pub struct RegularPolygon {
+    pub(crate) sides: u32,
+    pub(crate) radius: f32,
+}
+
+impl BuildCommands for RegularPolygon {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::RegularPolygon)
+            .push_param_uint(self.sides)
+            .push_param_float(self.radius);
+    }
+}
+
+impl SignedDistance for RegularPolygon {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RegularPolygonTemplate;
+impl SDF2DNodeTemplateTrait for RegularPolygonTemplate {
+    fn name(&self) -> &'static str {
+        "RegularPolygon"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "sides",
+                ValueType::Scalar(6.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "radius",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<SDF2DNode, Diagnostic> {
+        let sides = input_params.scalar("sides")?;
+        let radius = input_params.scalar("radius")?;
+        Ok(RegularPolygon {
+            sides: sides.round().max(3.) as u32,
+            radius,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,65 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SDF2DNode, SignedDistance,
+};
+
+/// Revolves a 2D profile around the Y axis.
+/// The X axis of the profile is the distance from the Y axis, minus `offset`.
+#[derive(Debug, Clone)]
+pub struct Revolve {
+    pub(crate) offset: f32,
+    pub(crate) profile: Box<SDF2DNode>,
+}
+
+impl BuildCommands for Revolve {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::RevolvePush)
+            .push_param_float(self.offset);
+        self.profile.build_commands(builder);
+        builder.push_command(CSGCommandType::RevolvePop);
+    }
+}
+
+impl SignedDistance for Revolve {
+    fn distance_kind(&self) -> DistanceKind {
+        self.profile.distance_kind()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RevolveTemplate;
+impl CSGNodeTemplateTrait for RevolveTemplate {
+    fn name(&self) -> &'static str {
+        "Revolve"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "profile",
+                ValueType::SDF2D(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "offset",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let profile = input_params.sdf_2d("profile")?;
+        let offset = input_params.scalar("offset")?;
+        Ok(Revolve {
+            offset,
+            profile: Box::new(profile),
+        }
+        .into())
+    }
+}
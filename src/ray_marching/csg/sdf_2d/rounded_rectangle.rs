@@ -0,0 +1,71 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, DistanceKind, SDF2DNode, SDF2DNodeTemplateTrait, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct RoundedRectangle {
+    pub(crate) half_width: f32,
+    pub(crate) half_height: f32,
+    pub(crate) rounding: f32,
+}
+
+impl BuildCommands for RoundedRectangle {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::RoundedRectangle)
+            .push_param_float(self.half_width)
+            .push_param_float(self.half_height)
+            .push_param_float(self.rounding);
+    }
+}
+
+impl SignedDistance for RoundedRectangle {
+    fn distance_kind(&self) -> DistanceKind {
+        DistanceKind::Exact
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RoundedRectangleTemplate;
+impl SDF2DNodeTemplateTrait for RoundedRectangleTemplate {
+    fn name(&self) -> &'static str {
+        "RoundedRectangle"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "half_width",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "half_height",
+                ValueType::Scalar(1.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "rounding",
+                ValueType::Scalar(0.1),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<SDF2DNode, Diagnostic> {
+        let half_width = input_params.scalar("half_width")?;
+        let half_height = input_params.scalar("half_height")?;
+        let rounding = input_params.scalar("rounding")?;
+        Ok(RoundedRectangle {
+            half_width,
+            half_height,
+            rounding,
+        }
+        .into())
+    }
+}
@@ -0,0 +1,326 @@
+use egui_node_graph::InputParamKind;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+use ab_glyph::{Font, FontRef, OutlineCurve};
+use eframe::egui;
+use nalgebra::Vector2;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{
+    CSGCommandBufferBuilder, CSGCommandType, TEXT_ATLAS_MAX_SIZE,
+};
+use crate::ray_marching::csg::{
+    BuildCommands, DistanceKind, SDF2DNode, SDF2DNodeTemplateTrait, SignedDistance,
+};
+
+/// Number of grid samples per em.
+const SAMPLES_PER_EM: f32 = 32.0;
+/// Number of line segments each Bezier curve of a glyph outline is flattened into.
+const CURVE_SEGMENTS: usize = 8;
+/// Number of grids kept in the cache, grids of old edits are forgotten beyond this.
+const MAX_CACHED_GRIDS: usize = 64;
+
+/// Single line of text, centered on the origin.
+#[derive(Debug, Clone)]
+pub struct Text {
+    pub(crate) grid: Arc<TextGrid>,
+}
+
+/// Signed distances to the glyph outlines of a line of text, sampled on a regular grid.
+/// The samples are uploaded to the text atlas texture, see
+/// [`CSGCommandBufferBuilder::push_param_text_grid`].
+#[derive(Debug)]
+pub(crate) struct TextGrid {
+    /// Position of the first sample.
+    min: Vector2<f32>,
+    cell_size: f32,
+    /// Number of samples per row.
+    width: u32,
+    /// Samples in row-major order.
+    samples: Vec<f32>,
+}
+
+impl BuildCommands for Text {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::Text)
+            .push_param_float(self.grid.min.x)
+            .push_param_float(self.grid.min.y)
+            .push_param_float(self.grid.cell_size)
+            .push_param_uint(self.grid.width)
+            .push_param_uint(self.grid.height())
+            .push_param_text_grid(&self.grid);
+    }
+}
+
+impl SignedDistance for Text {
+    fn distance_kind(&self) -> DistanceKind {
+        // The samples are interpolated, and outside the grid only the distance to it is known.
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TextTemplate;
+impl SDF2DNodeTemplateTrait for TextTemplate {
+    fn name(&self) -> &'static str {
+        "Text"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "text",
+                ValueType::Text("Text".to_string()),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "font",
+                ValueType::Text("Ubuntu-Light".to_string()),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "size",
+                ValueType::Scalar(1.0),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<SDF2DNode, Diagnostic> {
+        let text = input_params.text("text")?;
+        let font = input_params.text("font")?;
+        let size = input_params.scalar("size")?;
+        if !egui::FontDefinitions::default()
+            .font_data
+            .contains_key(&font)
+        {
+            return Err(Diagnostic::InvalidInput {
+                input: "font".to_string(),
+                reason: "is not a bundled font",
+            });
+        }
+        if size <= 0.0 {
+            return Err(Diagnostic::InvalidInput {
+                input: "size".to_string(),
+                reason: "must be positive",
+            });
+        }
+
+        let grid = TextGrid::rasterize_cached(text, font, size)?;
+        if grid.width() > TEXT_ATLAS_MAX_SIZE || grid.height() > TEXT_ATLAS_MAX_SIZE {
+            return Err(Diagnostic::InvalidInput {
+                input: "text".to_string(),
+                reason: "is too long to render",
+            });
+        }
+        Ok(Text { grid }.into())
+    }
+}
+
+/// Text, font name and the bits of the size of a rasterized grid.
+type CacheKey = (String, String, u32);
+
+enum CachedGrid {
+    Rasterizing,
+    /// `None` for text without outlines.
+    Done(Option<Arc<TextGrid>>),
+}
+
+/// Rasterizing is too slow to repeat every time the graph is evaluated, so grids are cached.
+fn cache() -> &'static Mutex<HashMap<CacheKey, CachedGrid>> {
+    static CACHE: OnceLock<Mutex<HashMap<CacheKey, CachedGrid>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+impl TextGrid {
+    /// The grid of a text, rasterized on a background thread as long texts take a while. Until it
+    /// is done, this fails with [`Diagnostic::Rasterizing`].
+    fn rasterize_cached(text: String, font: String, size: f32) -> Result<Arc<Self>, Diagnostic> {
+        let mut grids = cache().lock().unwrap();
+        let key = (text, font, size.to_bits());
+        match grids.get(&key) {
+            Some(CachedGrid::Done(Some(grid))) => return Ok(grid.clone()),
+            Some(CachedGrid::Done(None)) => {
+                return Err(Diagnostic::InvalidInput {
+                    input: "text".to_string(),
+                    reason: "has no outlines in the font",
+                })
+            }
+            Some(CachedGrid::Rasterizing) => return Err(Diagnostic::Rasterizing),
+            None => {
+                // Forget grids of old edits, instead of growing forever while typing.
+                if grids.len() >= MAX_CACHED_GRIDS {
+                    grids.retain(|_, cached| matches!(cached, CachedGrid::Rasterizing));
+                }
+                grids.insert(key.clone(), CachedGrid::Rasterizing);
+                let key = key.clone();
+                thread::spawn(move || {
+                    let (text, font, size) = &key;
+                    let grid = Self::rasterize(text, font, f32::from_bits(*size)).map(Arc::new);
+                    cache().lock().unwrap().insert(key, CachedGrid::Done(grid));
+                });
+            }
+        }
+
+        Err(Diagnostic::Rasterizing)
+    }
+
+    /// Whether grids are being rasterized, so that the graph should be evaluated again soon.
+    pub(crate) fn is_rasterizing() -> bool {
+        cache()
+            .lock()
+            .unwrap()
+            .values()
+            .any(|cached| matches!(cached, CachedGrid::Rasterizing))
+    }
+
+    pub(crate) fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub(crate) fn height(&self) -> u32 {
+        self.samples.len() as u32 / self.width
+    }
+
+    /// Samples in row-major order.
+    pub(crate) fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// Rasterize text in one of egui's bundled fonts, `size` is the size of an em.
+    fn rasterize(text: &str, font_name: &str, size: f32) -> Option<Self> {
+        let font_definitions = egui::FontDefinitions::default();
+        let font_data = font_definitions.font_data.get(font_name)?;
+        let font = FontRef::try_from_slice_and_index(&font_data.font, font_data.index).ok()?;
+        let scale = size / font.units_per_em()?;
+
+        // Lay out the glyphs on a single line, and flatten their outlines into line segments.
+        let mut segments = Vec::new();
+        let mut caret = 0.0;
+        let mut previous_glyph = None;
+        for c in text.chars() {
+            let glyph = font.glyph_id(c);
+            if let Some(previous_glyph) = previous_glyph {
+                caret += font.kern_unscaled(previous_glyph, glyph);
+            }
+            if let Some(outline) = font.outline(glyph) {
+                let offset = Vector2::new(caret, 0.0);
+                for curve in outline.curves {
+                    flatten_curve(&curve, &mut segments, |point| {
+                        (Vector2::new(point.x, point.y) + offset) * scale
+                    });
+                }
+            }
+            caret += font.h_advance_unscaled(glyph);
+            previous_glyph = Some(glyph);
+        }
+        if segments.is_empty() {
+            return None;
+        }
+
+        let mut min = segments[0][0];
+        let mut max = segments[0][0];
+        for point in segments.iter().flatten() {
+            min = min.inf(point);
+            max = max.sup(point);
+        }
+
+        // Pad the bounds, so the outlines don't touch the edges of the grid.
+        let padding = 0.1 * size;
+        let cell_size = size / SAMPLES_PER_EM;
+        let resolution =
+            (max - min).map(|extent| ((extent + 2.0 * padding) / cell_size) as u32 + 2);
+
+        // Center the text on the origin.
+        let grid_extent = resolution.map(|samples| (samples - 1) as f32) * cell_size;
+        let center = (min + max) / 2.0;
+        for point in segments.iter_mut().flatten() {
+            *point -= center;
+        }
+        let min = -grid_extent / 2.0;
+
+        let samples = (0..resolution.y)
+            .flat_map(|y| (0..resolution.x).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let pos = min + Vector2::new(x as f32, y as f32) * cell_size;
+                signed_distance(&segments, pos)
+            })
+            .collect();
+
+        Some(Self {
+            min,
+            cell_size,
+            width: resolution.x,
+            samples,
+        })
+    }
+}
+
+fn flatten_curve(
+    curve: &OutlineCurve,
+    segments: &mut Vec<[Vector2<f32>; 2]>,
+    transform: impl Fn(ab_glyph::Point) -> Vector2<f32>,
+) {
+    match *curve {
+        OutlineCurve::Line(p0, p1) => segments.push([transform(p0), transform(p1)]),
+        OutlineCurve::Quad(p0, p1, p2) => {
+            let [p0, p1, p2] = [p0, p1, p2].map(transform);
+            let point = |t: f32| {
+                let s = 1.0 - t;
+                p0 * (s * s) + p1 * (2.0 * s * t) + p2 * (t * t)
+            };
+            flatten(point, segments);
+        }
+        OutlineCurve::Cubic(p0, p1, p2, p3) => {
+            let [p0, p1, p2, p3] = [p0, p1, p2, p3].map(transform);
+            let point = |t: f32| {
+                let s = 1.0 - t;
+                p0 * (s * s * s)
+                    + p1 * (3.0 * s * s * t)
+                    + p2 * (3.0 * s * t * t)
+                    + p3 * (t * t * t)
+            };
+            flatten(point, segments);
+        }
+    }
+}
+
+fn flatten(point: impl Fn(f32) -> Vector2<f32>, segments: &mut Vec<[Vector2<f32>; 2]>) {
+    let mut previous = point(0.0);
+    for i in 1..=CURVE_SEGMENTS {
+        let next = point(i as f32 / CURVE_SEGMENTS as f32);
+        segments.push([previous, next]);
+        previous = next;
+    }
+}
+
+/// Distance to the closest segment, negative inside the outlines by the non-zero winding rule.
+fn signed_distance(segments: &[[Vector2<f32>; 2]], pos: Vector2<f32>) -> f32 {
+    let mut distance_squared = f32::INFINITY;
+    let mut winding_number = 0;
+    for [a, b] in segments {
+        let ab = b - a;
+        let ap = pos - a;
+        let h = (ap.dot(&ab) / ab.norm_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        distance_squared = distance_squared.min((ap - ab * h).norm_squared());
+
+        // Count crossings of the ray from `pos` in the +x direction.
+        let side = ab.perp(&ap);
+        if a.y <= pos.y && pos.y < b.y && side > 0.0 {
+            winding_number += 1;
+        } else if b.y <= pos.y && pos.y < a.y && side < 0.0 {
+            winding_number -= 1;
+        }
+    }
+
+    let distance = distance_squared.sqrt();
+    if winding_number != 0 {
+        -distance
+    } else {
+        distance
+    }
+}
@@ -0,0 +1,67 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Mirrors the positive side of the child across the axis planes through the origin.
+#[derive(Debug, Clone)]
+pub struct Mirror {
+    /// Axes to mirror along, any non-zero component enables mirroring along that axis.
+    pub(crate) axes: [f32; 3],
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl BuildCommands for Mirror {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::MirrorPush)
+            .push_param_vec3(self.axes.map(|axis| if axis != 0. { 1. } else { 0. }));
+        self.child.build_commands(builder);
+        builder.push_command(CSGCommandType::MirrorPop);
+    }
+}
+
+impl SignedDistance for Mirror {
+    fn distance_kind(&self) -> DistanceKind {
+        // Parts of the child on the negative side of a mirror plane are cut off, which makes the
+        // distance a bound.
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MirrorTemplate;
+impl CSGNodeTemplateTrait for MirrorTemplate {
+    fn name(&self) -> &'static str {
+        "Mirror"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "axes",
+                ValueType::Vec3([1., 0., 0.]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let axes = input_params.vec3("axes")?;
+        Ok(Mirror {
+            axes,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
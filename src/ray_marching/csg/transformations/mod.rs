@@ -0,0 +1,13 @@
+mod mirror;
+mod radial_symmetry;
+mod repeat;
+mod rotation;
+mod scale;
+mod translation;
+
+pub use mirror::*;
+pub use radial_symmetry::*;
+pub use repeat::*;
+pub use rotation::*;
+pub use scale::*;
+pub use translation::*;
@@ -0,0 +1,84 @@
+use egui_node_graph::InputParamKind;
+use nalgebra::{UnitQuaternion, Vector3};
+use std::f32::consts::PI;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Repeats the child in equally sized segments around an axis through the origin.
+/// The segment around the positive X axis (or its rotation towards `axis`) is the one repeated.
+#[derive(Debug, Clone)]
+pub struct RadialSymmetry {
+    pub(crate) axis: [f32; 3],
+    pub(crate) segments: f32,
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl BuildCommands for RadialSymmetry {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        // The shader folds space around the Y axis, so rotate the Y axis onto the symmetry axis.
+        let axis = Vector3::from(self.axis)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+        let rotation = UnitQuaternion::rotation_between(&Vector3::y(), &axis)
+            .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI));
+
+        builder
+            .push_command(CSGCommandType::RadialSymmetryPush)
+            .push_param_mat3(rotation.to_rotation_matrix().into_inner().into())
+            .push_param_float(self.segments.round().max(1.));
+        self.child.build_commands(builder);
+        builder.push_command(CSGCommandType::RadialSymmetryPop);
+    }
+}
+
+impl SignedDistance for RadialSymmetry {
+    fn distance_kind(&self) -> DistanceKind {
+        // Parts of the child outside of its segment are cut off, which makes the distance a bound.
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RadialSymmetryTemplate;
+impl CSGNodeTemplateTrait for RadialSymmetryTemplate {
+    fn name(&self) -> &'static str {
+        "RadialSymmetry"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "axis",
+                ValueType::Vec3([0., 1., 0.]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "segments",
+                ValueType::Scalar(6.),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let axis = input_params.vec3("axis")?;
+        let segments = input_params.scalar("segments")?;
+        Ok(RadialSymmetry {
+            axis,
+            segments,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
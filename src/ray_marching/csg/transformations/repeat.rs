@@ -0,0 +1,77 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Repeats the child in a grid of cells, centered on the origin.
+#[derive(Debug, Clone)]
+pub struct Repeat {
+    /// Size of a cell along each axis. Axes with a period of zero are not repeated.
+    pub(crate) period: [f32; 3],
+    /// Number of repetitions along each axis. Axes with a count of zero are repeated infinitely.
+    pub(crate) count: [f32; 3],
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl BuildCommands for Repeat {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::RepeatPush)
+            .push_param_vec3(self.period.map(|period| period.max(0.)))
+            .push_param_vec3(self.count.map(|count| count.round().max(0.)));
+        self.child.build_commands(builder);
+        builder.push_command(CSGCommandType::RepeatPop);
+    }
+}
+
+impl SignedDistance for Repeat {
+    fn distance_kind(&self) -> DistanceKind {
+        // Only the nearest cell is evaluated, which underestimates the distance whenever the
+        // child extends beyond its cell.
+        DistanceKind::Bound
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RepeatTemplate;
+impl CSGNodeTemplateTrait for RepeatTemplate {
+    fn name(&self) -> &'static str {
+        "Repeat"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "period",
+                ValueType::Vec3([3.; 3]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+            (
+                "count",
+                ValueType::Vec3([0.; 3]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let period = input_params.vec3("period")?;
+        let count = input_params.vec3("count")?;
+        Ok(Repeat {
+            period,
+            count,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
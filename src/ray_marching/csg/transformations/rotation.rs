@@ -0,0 +1,69 @@
+use egui_node_graph::InputParamKind;
+use nalgebra::Rotation3;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct Rotation {
+    /// Euler angles (roll, pitch, yaw) in degrees.
+    pub(crate) angles: [f32; 3],
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl BuildCommands for Rotation {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        let [roll, pitch, yaw] = self.angles.map(f32::to_radians);
+        // The shader rotates the lookup position, so it needs the inverse rotation.
+        let inv_rotation = Rotation3::from_euler_angles(roll, pitch, yaw).inverse();
+
+        builder
+            .push_command(CSGCommandType::RotationPush)
+            .push_param_mat3(inv_rotation.into_inner().into());
+        self.child.build_commands(builder);
+        builder.push_command(CSGCommandType::RotationPop);
+    }
+}
+
+impl SignedDistance for Rotation {
+    fn distance_kind(&self) -> DistanceKind {
+        self.child.distance_kind()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct RotationTemplate;
+impl CSGNodeTemplateTrait for RotationTemplate {
+    fn name(&self) -> &'static str {
+        "Rotation"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "angles",
+                ValueType::Vec3([0.; 3]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let angles = input_params.vec3("angles")?;
+        Ok(Rotation {
+            angles,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
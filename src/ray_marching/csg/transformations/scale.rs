@@ -0,0 +1,82 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+/// Smallest magnitude of a scale factor component, zero would divide space by zero.
+const MIN_FACTOR: f32 = 1e-3;
+
+#[derive(Debug, Clone)]
+pub struct Scale {
+    pub(crate) factor: [f32; 3],
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl BuildCommands for Scale {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        // Scaling space by a non-uniform factor stretches distances by at least the smallest
+        // factor, so scaling the child's distance by it keeps the result a valid bound.
+        let min_factor = self
+            .factor
+            .iter()
+            .fold(f32::INFINITY, |a, b| a.min(b.abs()));
+
+        builder
+            .push_command(CSGCommandType::ScalePush)
+            .push_param_vec3(self.factor);
+        self.child.build_commands(builder);
+        builder
+            .push_command(CSGCommandType::ScalePop)
+            .push_param_float(min_factor);
+    }
+}
+
+impl SignedDistance for Scale {
+    fn distance_kind(&self) -> DistanceKind {
+        let [x, y, z] = self.factor;
+        if x == y && y == z {
+            self.child.distance_kind()
+        } else {
+            DistanceKind::Bound
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ScaleTemplate;
+impl CSGNodeTemplateTrait for ScaleTemplate {
+    fn name(&self) -> &'static str {
+        "Scale"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "factor",
+                ValueType::Vec3([1.; 3]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let factor = input_params
+            .vec3("factor")?
+            .map(|component| component.abs().max(MIN_FACTOR).copysign(component));
+        Ok(Scale {
+            factor,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
@@ -0,0 +1,63 @@
+use egui_node_graph::InputParamKind;
+
+use crate::csg_node_graph::ValueType;
+use crate::diagnostics::{Diagnostic, InputParams};
+use crate::ray_marching::csg::builder::{CSGCommandBufferBuilder, CSGCommandType};
+use crate::ray_marching::csg::{
+    BuildCommands, CSGNode, CSGNodeTemplateTrait, DistanceKind, SignedDistance,
+};
+
+#[derive(Debug, Clone)]
+pub struct Translation {
+    pub(crate) offset: [f32; 3],
+    pub(crate) child: Box<CSGNode>,
+}
+
+impl BuildCommands for Translation {
+    fn build_commands(&self, builder: &mut CSGCommandBufferBuilder) {
+        builder
+            .push_command(CSGCommandType::TranslationPush)
+            .push_param_vec3(self.offset);
+        self.child.build_commands(builder);
+        builder.push_command(CSGCommandType::TranslationPop);
+    }
+}
+
+impl SignedDistance for Translation {
+    fn distance_kind(&self) -> DistanceKind {
+        self.child.distance_kind()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TranslationTemplate;
+impl CSGNodeTemplateTrait for TranslationTemplate {
+    fn name(&self) -> &'static str {
+        "Translation"
+    }
+
+    fn input_params(&self) -> Vec<(&'static str, ValueType, InputParamKind)> {
+        vec![
+            (
+                "SDF",
+                ValueType::CSGNode(None),
+                InputParamKind::ConnectionOnly,
+            ),
+            (
+                "offset",
+                ValueType::Vec3([0.; 3]),
+                InputParamKind::ConnectionOrConstant,
+            ),
+        ]
+    }
+
+    fn evaluate(&self, input_params: &InputParams) -> Result<CSGNode, Diagnostic> {
+        let child = input_params.csg_node("SDF")?;
+        let offset = input_params.vec3("offset")?;
+        Ok(Translation {
+            offset,
+            child: Box::new(child),
+        }
+        .into())
+    }
+}
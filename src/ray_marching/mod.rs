@@ -1,2 +1,3 @@
 pub(crate) mod csg;
 pub(crate) mod renderer;
+pub(crate) mod sdf_grid;
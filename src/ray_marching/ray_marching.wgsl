@@ -26,10 +26,14 @@ struct Uniforms {
     inv_proj: mat4x4<f32>,
     /// Inversed view matrix.
     inv_view: mat4x4<f32>,
+    /// Time of the animation clock in seconds.
+    time: f32,
 }
 
 @group(0) @binding(2) var<uniform> uniforms: Uniforms;
 
+const PI: f32 = 3.14159265358979323846264338327950288;
+
 // Number of antialiasing samples in each direction (total samples = aa_samples * aa_samples)
 const aa_samples: u32 = 4u;
 
@@ -110,8 +114,8 @@ fn ray_march(origin: vec3<f32>, direction: vec3<f32>) -> vec3<f32> {
             break;
         }
 
-        // March ray forward
-        dist += scene_dist;
+        // March ray forward, scaled down for scenes whose SDF overestimates distances
+        dist += scene_dist * csg_commands.step_scale;
     }
 
     // Ray-trace the floor plane
@@ -145,6 +149,8 @@ fn calculate_normal(pos: vec3<f32>) -> vec3<f32> {
 
 struct CSGCommandBuffer {
     cmd_count: u32,
+    /// Inverse of the Lipschitz constant of the scene's SDF.
+    step_scale: f32,
     buffer: array<u32>,
 }
 
@@ -161,29 +167,58 @@ fn csg_pop_f32() -> f32 {
     return bitcast<f32>(csg_pop_u32());
 }
 
+fn csg_pop_vec2() -> vec2<f32> {
+    return vec2<f32>(csg_pop_f32(), csg_pop_f32());
+}
+
 fn csg_pop_vec3() -> vec3<f32> {
     return vec3<f32>(csg_pop_f32(), csg_pop_f32(), csg_pop_f32());
 }
 
+fn csg_pop_mat3() -> mat3x3<f32> {
+    return mat3x3<f32>(csg_pop_vec3(), csg_pop_vec3(), csg_pop_vec3());
+}
+
 fn csg_pop_command_type() -> u32 {
     return csg_pop_u32();
 }
 
 // Execution context
+// Scenes nested deeper than the stacks are reported in the node graph. Values that don't fit are
+// dropped so that such scenes render wrong instead of indexing out of bounds.
 const value_stack_max_size: u32 = 32u;
 var<private> value_stack_data: array<f32, value_stack_max_size>;
 var<private> value_stack_size: u32;
 
 fn pop_value() -> f32 {
     value_stack_size--;
-    return value_stack_data[value_stack_size];
+    return value_stack_data[min(value_stack_size, value_stack_max_size - 1u)];
 }
 
 fn push_value(value: f32) {
-    value_stack_data[value_stack_size] = value;
+    if (value_stack_size < value_stack_max_size) {
+        value_stack_data[value_stack_size] = value;
+    }
     value_stack_size++;
 }
 
+// Positions saved by space transformations, restored when the transformation is popped.
+const pos_stack_max_size: u32 = 16u;
+var<private> pos_stack_data: array<vec3<f32>, pos_stack_max_size>;
+var<private> pos_stack_size: u32;
+
+fn pop_pos() -> vec3<f32> {
+    pos_stack_size--;
+    return pos_stack_data[min(pos_stack_size, pos_stack_max_size - 1u)];
+}
+
+fn push_pos(pos: vec3<f32>) {
+    if (pos_stack_size < pos_stack_max_size) {
+        pos_stack_data[pos_stack_size] = pos;
+    }
+    pos_stack_size++;
+}
+
 fn map_scene(pos: vec3<f32>) -> f32 {
     // Early return for empty scenes.
     if (csg_commands.cmd_count == 0u) {
@@ -192,11 +227,19 @@ fn map_scene(pos: vec3<f32>) -> f32 {
 
     // Reset pointers.
     value_stack_size = 0u;
+    pos_stack_size = 0u;
     csg_commands_ptr = 0u;
 
+    // Position in the space of the command currently being evaluated.
+    var cmd_pos = pos;
+
     for (var idx = 0u; idx < csg_commands.cmd_count; idx++) {
         let cmd_type = csg_pop_command_type();
-        push_value(eval_cmd(cmd_type, pos));
+        if (cmd_type >= 200u && cmd_type < 300u) {
+            cmd_pos = eval_space_cmd(cmd_type, cmd_pos);
+        } else {
+            push_value(eval_cmd(cmd_type, cmd_pos));
+        }
     }
 
     return pop_value();
@@ -211,6 +254,56 @@ fn eval_cmd(cmd_type: u32, pos: vec3<f32>) -> f32 {
         case 1u: {
             return eval_cmd_box(pos);
         }
+        case 2u: {
+            return eval_cmd_plane(pos);
+        }
+        case 3u: {
+            return eval_cmd_torus(pos);
+        }
+        case 4u: {
+            return eval_cmd_cylinder(pos);
+        }
+        case 5u: {
+            return eval_cmd_capsule(pos);
+        }
+        case 6u: {
+            return eval_cmd_cone(pos);
+        }
+        case 7u: {
+            return eval_cmd_ellipsoid(pos);
+        }
+        case 8u: {
+            return eval_cmd_rounded_box(pos);
+        }
+        case 9u: {
+            return eval_cmd_octahedron(pos);
+        }
+        case 10u: {
+            return eval_cmd_polyline(pos);
+        }
+        case 11u: {
+            return eval_cmd_quadratic_bezier(pos);
+        }
+        case 12u: {
+            return eval_cmd_cubic_bezier(pos);
+        }
+        case 13u: {
+            return eval_cmd_grid_sdf(pos);
+        }
+
+        // Fractals
+        case 50u: {
+            return eval_cmd_mandelbulb(pos);
+        }
+        case 51u: {
+            return eval_cmd_menger_sponge(pos);
+        }
+        case 52u: {
+            return eval_cmd_sierpinski_tetrahedron(pos);
+        }
+        case 53u: {
+            return eval_cmd_mandelbox(pos);
+        }
 
         // Binary operations
         case 100u: {
@@ -219,6 +312,76 @@ fn eval_cmd(cmd_type: u32, pos: vec3<f32>) -> f32 {
         case 101u: {
             return eval_cmd_subtract();
         }
+        case 102u: {
+            return eval_cmd_intersect();
+        }
+        case 103u: {
+            return eval_cmd_smooth_union();
+        }
+        case 104u: {
+            return eval_cmd_smooth_subtract();
+        }
+        case 105u: {
+            return eval_cmd_smooth_intersect();
+        }
+        case 106u: {
+            return eval_cmd_morph();
+        }
+        case 107u: {
+            return eval_cmd_chamfer_union();
+        }
+        case 108u: {
+            return eval_cmd_stairs_union();
+        }
+        case 109u: {
+            return eval_cmd_stairs_subtract();
+        }
+        case 110u: {
+            return eval_cmd_columns_union();
+        }
+        case 111u: {
+            return eval_cmd_groove();
+        }
+        case 112u: {
+            return eval_cmd_tongue();
+        }
+        case 113u: {
+            return eval_cmd_xor();
+        }
+
+        // Distance modifiers
+        case 300u: {
+            return eval_cmd_sine_displace(pos);
+        }
+        case 301u: {
+            return eval_cmd_onion();
+        }
+        case 302u: {
+            return eval_cmd_offset();
+        }
+        case 303u: {
+            return eval_cmd_displace(pos);
+        }
+
+        // 2D primitives
+        case 400u: {
+            return eval_cmd_circle(pos.xy);
+        }
+        case 401u: {
+            return eval_cmd_rectangle(pos.xy);
+        }
+        case 402u: {
+            return eval_cmd_regular_polygon(pos.xy);
+        }
+        case 403u: {
+            return eval_cmd_rounded_rectangle(pos.xy);
+        }
+        case 404u: {
+            return eval_cmd_text(pos.xy);
+        }
+        case 405u: {
+            return eval_cmd_polygon(pos.xy);
+        }
 
         default: {
             return 0.0;
@@ -227,22 +390,419 @@ fn eval_cmd(cmd_type: u32, pos: vec3<f32>) -> f32 {
 }
 
 fn eval_cmd_sphere(pos: vec3<f32>) -> f32 {
-    let center = csg_pop_vec3();
     let radius = csg_pop_f32();
-    return length(pos - center) - radius;
+    return length(pos) - radius;
 }
 
 fn eval_cmd_box(pos: vec3<f32>) -> f32 {
-    let center = csg_pop_vec3();
     let radius = csg_pop_vec3();
-    let q = abs(pos - center) - radius;
+    let q = abs(pos) - radius;
     return length(max(q, vec3(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);
 }
 
+// Primitives below are from: https://iquilezles.org/articles/distfunctions/
+
+fn eval_cmd_plane(pos: vec3<f32>) -> f32 {
+    let normal = csg_pop_vec3();
+    let offset = csg_pop_f32();
+    return dot(pos, normal) + offset;
+}
+
+fn eval_cmd_torus(pos: vec3<f32>) -> f32 {
+    let major_radius = csg_pop_f32();
+    let minor_radius = csg_pop_f32();
+    let q = vec2<f32>(length(pos.xz) - major_radius, pos.y);
+    return length(q) - minor_radius;
+}
+
+fn eval_cmd_cylinder(pos: vec3<f32>) -> f32 {
+    let radius = csg_pop_f32();
+    let half_height = csg_pop_f32();
+    let d = abs(vec2<f32>(length(pos.xz), pos.y)) - vec2<f32>(radius, half_height);
+    return min(max(d.x, d.y), 0.0) + length(max(d, vec2(0.0)));
+}
+
+fn eval_cmd_capsule(pos: vec3<f32>) -> f32 {
+    let radius = csg_pop_f32();
+    let half_height = csg_pop_f32();
+    let q = vec3<f32>(pos.x, pos.y - clamp(pos.y, -half_height, half_height), pos.z);
+    return length(q) - radius;
+}
+
+fn eval_cmd_cone(pos: vec3<f32>) -> f32 {
+    let half_height = csg_pop_f32();
+    let bottom_radius = csg_pop_f32();
+    let top_radius = csg_pop_f32();
+
+    let q = vec2<f32>(length(pos.xz), pos.y);
+    let k1 = vec2<f32>(top_radius, half_height);
+    let k2 = vec2<f32>(top_radius - bottom_radius, 2.0 * half_height);
+    let cap_radius = select(top_radius, bottom_radius, q.y < 0.0);
+    let ca = vec2<f32>(q.x - min(q.x, cap_radius), abs(q.y) - half_height);
+    let cb = q - k1 + k2 * clamp(dot(k1 - q, k2) / dot(k2, k2), 0.0, 1.0);
+    let s = select(1.0, -1.0, cb.x < 0.0 && ca.y < 0.0);
+    return s * sqrt(min(dot(ca, ca), dot(cb, cb)));
+}
+
+fn eval_cmd_ellipsoid(pos: vec3<f32>) -> f32 {
+    let radius = csg_pop_vec3();
+    let k0 = length(pos / radius);
+    let k1 = length(pos / (radius * radius));
+    return k0 * (k0 - 1.0) / k1;
+}
+
+fn eval_cmd_rounded_box(pos: vec3<f32>) -> f32 {
+    let radius = csg_pop_vec3();
+    let rounding = csg_pop_f32();
+    let q = abs(pos) - radius + rounding;
+    return length(max(q, vec3(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0) - rounding;
+}
+
+fn eval_cmd_octahedron(pos: vec3<f32>) -> f32 {
+    let size = csg_pop_f32();
+    let p = abs(pos);
+    let m = p.x + p.y + p.z - size;
+
+    var q: vec3<f32>;
+    if (3.0 * p.x < m) {
+        q = p.xyz;
+    } else if (3.0 * p.y < m) {
+        q = p.yzx;
+    } else if (3.0 * p.z < m) {
+        q = p.zxy;
+    } else {
+        return m * 0.57735027;
+    }
+
+    let k = clamp(0.5 * (q.z - q.y + size), 0.0, size);
+    return length(vec3<f32>(q.x, q.y - size + k, q.z - k));
+}
+
+// Distance to the line segment from a to b.
+fn segment_distance(pos: vec3<f32>, a: vec3<f32>, b: vec3<f32>) -> f32 {
+    let pa = pos - a;
+    let ba = b - a;
+    let h = clamp(dot(pa, ba) / max(dot(ba, ba), 1e-8), 0.0, 1.0);
+    return length(pa - ba * h);
+}
+
+fn eval_cmd_polyline(pos: vec3<f32>) -> f32 {
+    let point_count = csg_pop_u32();
+    var prev = csg_pop_vec3();
+    var d = length(pos - prev);
+    for (var i = 1u; i < point_count; i++) {
+        let next = csg_pop_vec3();
+        d = min(d, segment_distance(pos, prev, next));
+        prev = next;
+    }
+    let radius = csg_pop_f32();
+    return d - radius;
+}
+
+fn eval_cmd_quadratic_bezier(pos: vec3<f32>) -> f32 {
+    let start = csg_pop_vec3();
+    let control = csg_pop_vec3();
+    let end = csg_pop_vec3();
+    let radius = csg_pop_f32();
+
+    let a = control - start;
+    let b = start - 2.0 * control + end;
+    // A straight curve with evenly spaced control points has no quadratic term.
+    if (dot(b, b) < 1e-8) {
+        return segment_distance(pos, start, end) - radius;
+    }
+
+    // Solve the cubic for the parameter of the closest point on the curve.
+    let c = a * 2.0;
+    let d = start - pos;
+    let kk = 1.0 / dot(b, b);
+    let kx = kk * dot(a, b);
+    let ky = kk * (2.0 * dot(a, a) + dot(d, b)) / 3.0;
+    let kz = kk * dot(d, a);
+    let p = ky - kx * kx;
+    let q = kx * (2.0 * kx * kx - 3.0 * ky) + kz;
+    let h = q * q + 4.0 * p * p * p;
+
+    var dist_sq: f32;
+    if (h >= 0.0) {
+        let x = (vec2<f32>(sqrt(h), -sqrt(h)) - q) / 2.0;
+        let uv = sign(x) * pow(abs(x), vec2(1.0 / 3.0));
+        let t = clamp(uv.x + uv.y - kx, 0.0, 1.0);
+        let r = d + (c + b * t) * t;
+        dist_sq = dot(r, r);
+    } else {
+        let z = sqrt(-p);
+        let v = acos(q / (p * z * 2.0)) / 3.0;
+        let m = cos(v);
+        let n = sin(v) * 1.732050808;
+        // The third root can't be the closest.
+        let t = clamp(vec2<f32>(m + m, -n - m) * z - kx, vec2(0.0), vec2(1.0));
+        let r0 = d + (c + b * t.x) * t.x;
+        let r1 = d + (c + b * t.y) * t.y;
+        dist_sq = min(dot(r0, r0), dot(r1, r1));
+    }
+    return sqrt(dist_sq) - radius;
+}
+
+const cubic_bezier_segments: u32 = 16u;
+
+fn cubic_bezier_point(p0: vec3<f32>, p1: vec3<f32>, p2: vec3<f32>, p3: vec3<f32>, t: f32) -> vec3<f32> {
+    let s = 1.0 - t;
+    return s * s * s * p0 + 3.0 * s * s * t * p1 + 3.0 * s * t * t * p2 + t * t * t * p3;
+}
+
+fn eval_cmd_cubic_bezier(pos: vec3<f32>) -> f32 {
+    let p0 = csg_pop_vec3();
+    let p1 = csg_pop_vec3();
+    let p2 = csg_pop_vec3();
+    let p3 = csg_pop_vec3();
+    let radius = csg_pop_f32();
+
+    // Approximate the curve by evenly spaced line segments.
+    var prev = p0;
+    var d = length(pos - p0);
+    for (var i = 1u; i <= cubic_bezier_segments; i++) {
+        let next = cubic_bezier_point(p0, p1, p2, p3, f32(i) / f32(cubic_bezier_segments));
+        d = min(d, segment_distance(pos, prev, next));
+        prev = next;
+    }
+
+    // The curve strays from each segment by at most 1/8 of the squared parameter step times the
+    // largest second derivative, which is 6 times the largest second difference of the control
+    // points. The distance to the segments can exceed the distance to the curve by that much.
+    let second_difference = max(length(p0 - 2.0 * p1 + p2), length(p1 - 2.0 * p2 + p3));
+    let step = 1.0 / f32(cubic_bezier_segments);
+    let max_deviation = 0.75 * second_difference * step * step;
+    return d - max_deviation - radius;
+}
+
+struct GridSdfBounds {
+    /// Position of the first sample.
+    min: vec3<f32>,
+    /// Position of the last sample.
+    max: vec3<f32>,
+}
+
+@group(0) @binding(3) var grid_sdf: texture_3d<f32>;
+@group(0) @binding(4) var<uniform> grid_sdf_bounds: GridSdfBounds;
+
+fn grid_sdf_load(coords: vec3<i32>) -> f32 {
+    return textureLoad(grid_sdf, coords, 0).r;
+}
+
+fn eval_cmd_grid_sdf(pos: vec3<f32>) -> f32 {
+    // Outside the grid, add the distance to the grid to the distance at its boundary.
+    let q = clamp(pos, grid_sdf_bounds.min, grid_sdf_bounds.max);
+    let size = vec3<i32>(textureDimensions(grid_sdf));
+    let uvw = (q - grid_sdf_bounds.min) / (grid_sdf_bounds.max - grid_sdf_bounds.min) * vec3<f32>(size - 1);
+
+    // R32Float can't be filtered, so interpolate manually.
+    let c0 = clamp(vec3<i32>(floor(uvw)), vec3(0), size - 1);
+    let c1 = min(c0 + 1, size - 1);
+    let f = uvw - vec3<f32>(c0);
+    let d00 = mix(grid_sdf_load(c0), grid_sdf_load(vec3(c1.x, c0.y, c0.z)), f.x);
+    let d10 = mix(grid_sdf_load(vec3(c0.x, c1.y, c0.z)), grid_sdf_load(vec3(c1.x, c1.y, c0.z)), f.x);
+    let d01 = mix(grid_sdf_load(vec3(c0.x, c0.y, c1.z)), grid_sdf_load(vec3(c1.x, c0.y, c1.z)), f.x);
+    let d11 = mix(grid_sdf_load(vec3(c0.x, c1.y, c1.z)), grid_sdf_load(c1), f.x);
+    let d = mix(mix(d00, d10, f.y), mix(d01, d11, f.y), f.z);
+    return d + length(pos - q);
+}
+
+fn eval_cmd_circle(pos: vec2<f32>) -> f32 {
+    let radius = csg_pop_f32();
+    return length(pos) - radius;
+}
+
+fn eval_cmd_rectangle(pos: vec2<f32>) -> f32 {
+    let half_size = vec2<f32>(csg_pop_f32(), csg_pop_f32());
+    let d = abs(pos) - half_size;
+    return length(max(d, vec2(0.0))) + min(max(d.x, d.y), 0.0);
+}
+
+fn eval_cmd_regular_polygon(pos: vec2<f32>) -> f32 {
+    let sides = csg_pop_u32();
+    let radius = csg_pop_f32();
+
+    // Fold the angle into the segment around the Y axis.
+    let half_angle = PI / f32(sides);
+    let acs = vec2<f32>(cos(half_angle), sin(half_angle));
+    let angle = atan2(pos.x, pos.y);
+    let folded_angle = angle - 2.0 * half_angle * floor(angle / (2.0 * half_angle)) - half_angle;
+    var p = length(pos) * vec2<f32>(cos(folded_angle), abs(sin(folded_angle)));
+
+    // Distance to the edge of the segment
+    p -= radius * acs;
+    p.y += clamp(-p.y, 0.0, radius * acs.y);
+    return length(p) * sign(p.x);
+}
+
+// Squared distance to the edge from `a` to `b`, and whether the edge crosses the horizontal ray
+// from `pos` towards +X, for counting crossings to tell inside from outside.
+struct PolygonEdge {
+    distance_squared: f32,
+    crosses: bool,
+}
+
+fn polygon_edge(pos: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> PolygonEdge {
+    let e = a - b;
+    let w = pos - b;
+    let d = w - e * clamp(dot(w, e) / max(dot(e, e), 1e-12), 0.0, 1.0);
+    let c = vec3<bool>(pos.y >= b.y, pos.y < a.y, e.x * w.y > e.y * w.x);
+    return PolygonEdge(dot(d, d), all(c) || !any(c));
+}
+
+/// See: https://iquilezles.org/articles/distfunctions2d/
+fn eval_cmd_polygon(pos: vec2<f32>) -> f32 {
+    let vertex_count = csg_pop_u32();
+    let first = csg_pop_vec2();
+    var prev = first;
+    var d = dot(pos - first, pos - first);
+    var s = 1.0;
+    for (var i = 1u; i <= vertex_count; i++) {
+        // The last edge closes the polygon back to the first vertex.
+        var next = first;
+        if (i < vertex_count) {
+            next = csg_pop_vec2();
+        }
+        let edge = polygon_edge(pos, prev, next);
+        d = min(d, edge.distance_squared);
+        if (edge.crosses) {
+            s = -s;
+        }
+        prev = next;
+    }
+    return s * sqrt(d);
+}
+
+fn eval_cmd_rounded_rectangle(pos: vec2<f32>) -> f32 {
+    let half_size = vec2<f32>(csg_pop_f32(), csg_pop_f32());
+    let rounding = csg_pop_f32();
+    let d = abs(pos) - half_size + rounding;
+    return length(max(d, vec2(0.0))) + min(max(d.x, d.y), 0.0) - rounding;
+}
+
+/// Grids of the text nodes, stacked vertically.
+@group(0) @binding(5) var text_atlas: texture_2d<f32>;
+
+fn text_sample(atlas_row: u32, coords: vec2<u32>) -> f32 {
+    return textureLoad(text_atlas, vec2<i32>(coords + vec2(0u, atlas_row)), 0).r;
+}
+
+fn eval_cmd_text(pos: vec2<f32>) -> f32 {
+    let grid_min = vec2<f32>(csg_pop_f32(), csg_pop_f32());
+    let cell_size = csg_pop_f32();
+    let width = csg_pop_u32();
+    let height = csg_pop_u32();
+    let atlas_row = csg_pop_u32();
+
+    // Outside the grid, add the distance to the grid to the distance at its boundary.
+    let grid_max = grid_min + vec2<f32>(f32(width - 1u), f32(height - 1u)) * cell_size;
+    let q = clamp(pos, grid_min, grid_max);
+    let uv = (q - grid_min) / cell_size;
+
+    let c0 = min(vec2<u32>(uv), vec2<u32>(width - 2u, height - 2u));
+    let c1 = c0 + 1u;
+    let f = uv - vec2<f32>(c0);
+    let d0 = mix(text_sample(atlas_row, c0), text_sample(atlas_row, vec2(c1.x, c0.y)), f.x);
+    let d1 = mix(text_sample(atlas_row, vec2(c0.x, c1.y)), text_sample(atlas_row, c1), f.x);
+    return mix(d0, d1, f.y) + length(pos - q);
+}
+
+fn eval_cmd_mandelbulb(pos: vec3<f32>) -> f32 {
+    let iterations = csg_pop_u32();
+    let power = csg_pop_f32();
+
+    var z = pos;
+    var dr = 1.0;
+    var r = length(z);
+    for (var i = 0u; i < iterations && r < 2.0; i++) {
+        // Raise z to the given power in spherical coordinates.
+        let theta = acos(z.y / r) * power;
+        let phi = atan2(z.z, z.x) * power;
+        dr = pow(r, power - 1.0) * power * dr + 1.0;
+        z = pow(r, power) * vec3<f32>(sin(theta) * cos(phi), cos(theta), sin(theta) * sin(phi)) + pos;
+        r = length(z);
+    }
+    return 0.5 * log(r) * r / dr;
+}
+
+fn eval_cmd_menger_sponge(pos: vec3<f32>) -> f32 {
+    let iterations = csg_pop_u32();
+
+    let q = abs(pos) - vec3(1.0);
+    var dist = length(max(q, vec3(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);
+    var scale = 1.0;
+    for (var i = 0u; i < iterations; i++) {
+        // Carve out the cross in the center of each of the 27 sub-boxes.
+        let a = pos * scale - 2.0 * floor(pos * scale * 0.5) - 1.0;
+        scale *= 3.0;
+        let r = abs(1.0 - 3.0 * abs(a));
+        let da = max(r.x, r.y);
+        let db = max(r.y, r.z);
+        let dc = max(r.z, r.x);
+        let c = (min(da, min(db, dc)) - 1.0) / scale;
+        dist = max(dist, c);
+    }
+    return dist;
+}
+
+fn eval_cmd_sierpinski_tetrahedron(pos: vec3<f32>) -> f32 {
+    let iterations = csg_pop_u32();
+    let scale = csg_pop_f32();
+
+    var z = pos;
+    for (var i = 0u; i < iterations; i++) {
+        // Fold space onto the copy of the tetrahedron at the (1, 1, 1) corner.
+        if (z.x + z.y < 0.0) {
+            z = vec3<f32>(-z.y, -z.x, z.z);
+        }
+        if (z.x + z.z < 0.0) {
+            z = vec3<f32>(-z.z, z.y, -z.x);
+        }
+        if (z.y + z.z < 0.0) {
+            z = vec3<f32>(z.x, -z.z, -z.y);
+        }
+        z = z * scale - vec3(scale - 1.0);
+    }
+    return length(z) * pow(scale, -f32(iterations));
+}
+
+fn eval_cmd_mandelbox(pos: vec3<f32>) -> f32 {
+    let iterations = csg_pop_u32();
+    let scale = csg_pop_f32();
+
+    let min_radius2 = 0.25;
+    let fixed_radius2 = 1.0;
+
+    var z = pos;
+    var dr = 1.0;
+    for (var i = 0u; i < iterations; i++) {
+        // Box fold
+        z = clamp(z, vec3(-1.0), vec3(1.0)) * 2.0 - z;
+
+        // Sphere fold
+        let r2 = dot(z, z);
+        if (r2 < min_radius2) {
+            z *= fixed_radius2 / min_radius2;
+            dr *= fixed_radius2 / min_radius2;
+        } else if (r2 < fixed_radius2) {
+            z *= fixed_radius2 / r2;
+            dr *= fixed_radius2 / r2;
+        }
+
+        z = z * scale + pos;
+        dr = dr * abs(scale) + 1.0;
+    }
+    return length(z) / abs(dr);
+}
+
 fn eval_cmd_union() -> f32 {
-    let b = pop_value();
-    let a = pop_value();
-    return min(a, b);
+    let count = csg_pop_u32();
+    var result = pop_value();
+    for (var i = 1u; i < count; i++) {
+        result = min(result, pop_value());
+    }
+    return result;
 }
 
 fn eval_cmd_subtract() -> f32 {
@@ -250,3 +810,377 @@ fn eval_cmd_subtract() -> f32 {
     let a = pop_value();
     return max(a, -b);
 }
+
+fn eval_cmd_intersect() -> f32 {
+    let count = csg_pop_u32();
+    var result = pop_value();
+    for (var i = 1u; i < count; i++) {
+        result = max(result, pop_value());
+    }
+    return result;
+}
+
+/// Polynomial smooth minimum with blend radius `k`.
+/// See: https://iquilezles.org/articles/smin/
+fn smooth_min(a: f32, b: f32, k: f32) -> f32 {
+    let h = max(k - abs(a - b), 0.0) / max(k, 1e-6);
+    return min(a, b) - h * h * k * 0.25;
+}
+
+fn eval_cmd_smooth_union() -> f32 {
+    let count = csg_pop_u32();
+    let k = csg_pop_f32();
+    var result = pop_value();
+    for (var i = 1u; i < count; i++) {
+        result = smooth_min(pop_value(), result, k);
+    }
+    return result;
+}
+
+fn eval_cmd_smooth_subtract() -> f32 {
+    let k = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return -smooth_min(-a, b, k);
+}
+
+fn eval_cmd_smooth_intersect() -> f32 {
+    let k = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return -smooth_min(-a, -b, k);
+}
+
+fn eval_cmd_morph() -> f32 {
+    let t = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return mix(a, b, t);
+}
+
+// Decorative combinations from hg_sdf, see https://mercury.sexy/hg_sdf/
+
+fn eval_cmd_chamfer_union() -> f32 {
+    let radius = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return min(min(a, b), (a - radius + b) * sqrt(0.5));
+}
+
+fn stairs_union(a: f32, b: f32, radius: f32, steps: f32) -> f32 {
+    let s = radius / steps;
+    let u = b - radius;
+    let x = u - a + s;
+    return min(min(a, b), 0.5 * (u + a + abs(x - 2.0 * s * floor(x / (2.0 * s)) - s)));
+}
+
+fn eval_cmd_stairs_union() -> f32 {
+    let radius = csg_pop_f32();
+    let steps = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return stairs_union(a, b, radius, steps);
+}
+
+fn eval_cmd_stairs_subtract() -> f32 {
+    let radius = csg_pop_f32();
+    let steps = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return -stairs_union(-a, b, radius, steps);
+}
+
+fn eval_cmd_columns_union() -> f32 {
+    let radius = csg_pop_f32();
+    let columns = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    if (a >= radius || b >= radius) {
+        return min(a, b);
+    }
+
+    // Place the columns on the diagonal between both surfaces, in the (a, b) plane rotated by 45
+    // degrees.
+    let column_radius = radius * sqrt(2.0) / ((columns - 1.0) * 2.0 + sqrt(2.0));
+    var p = (vec2<f32>(a, b) + vec2<f32>(b, -a)) * sqrt(0.5);
+    p.x += column_radius * sqrt(2.0) - sqrt(2.0) / 2.0 * radius;
+    if (columns - 2.0 * floor(columns / 2.0) == 1.0) {
+        p.y += column_radius;
+    }
+    let period = column_radius * 2.0;
+    p.y = p.y + column_radius - period * floor((p.y + column_radius) / period) - column_radius;
+    return min(min(min(length(p) - column_radius, p.x), a), b);
+}
+
+fn eval_cmd_groove() -> f32 {
+    let depth = csg_pop_f32();
+    let width = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return max(a, min(a + depth, width - abs(b)));
+}
+
+fn eval_cmd_tongue() -> f32 {
+    let height = csg_pop_f32();
+    let width = csg_pop_f32();
+    let b = pop_value();
+    let a = pop_value();
+    return min(a, max(a - height, abs(b) - width));
+}
+
+fn eval_cmd_xor() -> f32 {
+    let b = pop_value();
+    let a = pop_value();
+    return max(min(a, b), -max(a, b));
+}
+
+fn eval_cmd_sine_displace(pos: vec3<f32>) -> f32 {
+    let amplitude = csg_pop_f32();
+    let frequency = csg_pop_f32();
+    let displacement = sin(frequency * pos);
+    return pop_value() + amplitude * displacement.x * displacement.y * displacement.z;
+}
+
+fn eval_cmd_onion() -> f32 {
+    let thickness = csg_pop_f32();
+    return abs(pop_value()) - thickness;
+}
+
+// Grows the shape by a positive offset, which also rounds its edges, and shrinks it by a negative one.
+fn eval_cmd_offset() -> f32 {
+    let offset = csg_pop_f32();
+    return pop_value() - offset;
+}
+
+fn eval_cmd_displace(pos: vec3<f32>) -> f32 {
+    let amplitude = csg_pop_f32();
+    let frequency = csg_pop_f32();
+    let octaves = csg_pop_u32();
+    let seed = csg_pop_u32();
+    return pop_value() + amplitude * fbm(pos * frequency, octaves, seed);
+}
+
+/// Hash function returning three independent random values.
+/// See: https://www.jcgt.org/published/0009/03/02/
+fn hash_pcg3d(value: vec3<u32>) -> vec3<u32> {
+    var v = value * 1664525u + 1013904223u;
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v ^= v >> vec3(16u);
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    return v;
+}
+
+/// Random gradient in [-1, 1]^3 for a lattice point of the noise.
+fn noise_gradient(cell: vec3<i32>, seed: u32) -> vec3<f32> {
+    let hash = hash_pcg3d(bitcast<vec3<u32>>(cell) ^ vec3(seed * 0x9e3779b9u));
+    return vec3<f32>(hash) / f32(0xffffffffu) * 2.0 - 1.0;
+}
+
+/// 3D gradient noise in roughly [-1, 1].
+/// See: https://iquilezles.org/articles/gradientnoise/
+fn gradient_noise(pos: vec3<f32>, seed: u32) -> f32 {
+    let i = vec3<i32>(floor(pos));
+    let f = fract(pos);
+    // Quintic interpolation, so the noise has continuous second derivatives.
+    let u = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+
+    let n000 = dot(noise_gradient(i + vec3(0, 0, 0), seed), f - vec3(0.0, 0.0, 0.0));
+    let n100 = dot(noise_gradient(i + vec3(1, 0, 0), seed), f - vec3(1.0, 0.0, 0.0));
+    let n010 = dot(noise_gradient(i + vec3(0, 1, 0), seed), f - vec3(0.0, 1.0, 0.0));
+    let n110 = dot(noise_gradient(i + vec3(1, 1, 0), seed), f - vec3(1.0, 1.0, 0.0));
+    let n001 = dot(noise_gradient(i + vec3(0, 0, 1), seed), f - vec3(0.0, 0.0, 1.0));
+    let n101 = dot(noise_gradient(i + vec3(1, 0, 1), seed), f - vec3(1.0, 0.0, 1.0));
+    let n011 = dot(noise_gradient(i + vec3(0, 1, 1), seed), f - vec3(0.0, 1.0, 1.0));
+    let n111 = dot(noise_gradient(i + vec3(1, 1, 1), seed), f - vec3(1.0, 1.0, 1.0));
+
+    return mix(
+        mix(mix(n000, n100, u.x), mix(n010, n110, u.x), u.y),
+        mix(mix(n001, n101, u.x), mix(n011, n111, u.x), u.y),
+        u.z
+    );
+}
+
+/// Fractal Brownian motion, doubling the frequency and halving the amplitude every octave.
+fn fbm(pos: vec3<f32>, octaves: u32, seed: u32) -> f32 {
+    var value = 0.0;
+    var amplitude = 1.0;
+    var p = pos;
+    for (var i = 0u; i < octaves; i++) {
+        value += amplitude * gradient_noise(p, seed + i);
+        p *= 2.0;
+        amplitude *= 0.5;
+    }
+    return value;
+}
+
+/// Evaluate a space transformation command, returning the position its children are evaluated at.
+fn eval_space_cmd(cmd_type: u32, pos: vec3<f32>) -> vec3<f32> {
+    switch (cmd_type) {
+        case 200u: {
+            return eval_cmd_translation_push(pos);
+        }
+        case 202u: {
+            return eval_cmd_rotation_push(pos);
+        }
+        case 204u: {
+            return eval_cmd_scale_push(pos);
+        }
+        case 205u: {
+            return eval_cmd_scale_pop();
+        }
+        case 206u: {
+            return eval_cmd_repeat_push(pos);
+        }
+        case 208u: {
+            return eval_cmd_mirror_push(pos);
+        }
+        case 210u: {
+            return eval_cmd_radial_symmetry_push(pos);
+        }
+        case 212u: {
+            return eval_cmd_twist_push(pos);
+        }
+        case 214u: {
+            return eval_cmd_bend_push(pos);
+        }
+        case 216u: {
+            return eval_cmd_taper_push(pos);
+        }
+        case 218u: {
+            return eval_cmd_elongate_push(pos);
+        }
+        case 220u: {
+            return eval_cmd_extrude_push(pos);
+        }
+        case 221u: {
+            return eval_cmd_extrude_pop();
+        }
+        case 222u: {
+            return eval_cmd_revolve_push(pos);
+        }
+
+        // Other transformations don't affect distances, popping them only restores the position.
+        default: {
+            return pop_pos();
+        }
+    }
+}
+
+fn eval_cmd_translation_push(pos: vec3<f32>) -> vec3<f32> {
+    let offset = csg_pop_vec3();
+    push_pos(pos);
+    return pos - offset;
+}
+
+fn eval_cmd_rotation_push(pos: vec3<f32>) -> vec3<f32> {
+    let inv_rotation = csg_pop_mat3();
+    push_pos(pos);
+    return inv_rotation * pos;
+}
+
+fn eval_cmd_scale_push(pos: vec3<f32>) -> vec3<f32> {
+    let factor = csg_pop_vec3();
+    push_pos(pos);
+    return pos / factor;
+}
+
+fn eval_cmd_scale_pop() -> vec3<f32> {
+    // Distances in the scaled space are scaled back by the smallest factor.
+    let min_factor = csg_pop_f32();
+    push_value(pop_value() * min_factor);
+    return pop_pos();
+}
+
+/// See: https://iquilezles.org/articles/sdfrepetition/
+fn eval_cmd_repeat_push(pos: vec3<f32>) -> vec3<f32> {
+    let period = csg_pop_vec3();
+    let count = csg_pop_vec3();
+    push_pos(pos);
+
+    // Index of the nearest cell. Limited repetitions are centered on the origin, so an even count
+    // puts the cell centers at half-integer indices.
+    let max_id = (count - 1.0) * 0.5;
+    let limited_id = clamp(round(pos / period + max_id) - max_id, -max_id, max_id);
+    let infinite_id = round(pos / period);
+    var id = select(limited_id, infinite_id, count == vec3(0.0));
+    id = select(id, vec3(0.0), period == vec3(0.0));
+
+    return pos - period * id;
+}
+
+fn eval_cmd_mirror_push(pos: vec3<f32>) -> vec3<f32> {
+    let axes = csg_pop_vec3();
+    push_pos(pos);
+    return select(pos, abs(pos), axes != vec3(0.0));
+}
+
+fn eval_cmd_radial_symmetry_push(pos: vec3<f32>) -> vec3<f32> {
+    let rotation = csg_pop_mat3();
+    let segments = csg_pop_f32();
+    push_pos(pos);
+
+    // Fold the angle around the Y axis into the segment centered on the X axis.
+    let local_pos = transpose(rotation) * pos;
+    let segment_angle = 2.0 * PI / segments;
+    let angle = atan2(local_pos.z, local_pos.x) + segment_angle * 0.5;
+    let folded_angle = angle - segment_angle * floor(angle / segment_angle) - segment_angle * 0.5;
+    let radius = length(local_pos.xz);
+    let folded_pos = vec3<f32>(radius * cos(folded_angle), local_pos.y, radius * sin(folded_angle));
+
+    return rotation * folded_pos;
+}
+
+fn eval_cmd_twist_push(pos: vec3<f32>) -> vec3<f32> {
+    let rate = csg_pop_f32();
+    push_pos(pos);
+    let c = cos(rate * pos.y);
+    let s = sin(rate * pos.y);
+    return vec3<f32>(c * pos.x - s * pos.z, pos.y, s * pos.x + c * pos.z);
+}
+
+fn eval_cmd_bend_push(pos: vec3<f32>) -> vec3<f32> {
+    let rate = csg_pop_f32();
+    push_pos(pos);
+    let c = cos(rate * pos.x);
+    let s = sin(rate * pos.x);
+    return vec3<f32>(c * pos.x - s * pos.y, s * pos.x + c * pos.y, pos.z);
+}
+
+const taper_min_scale: f32 = 0.1;
+
+fn eval_cmd_taper_push(pos: vec3<f32>) -> vec3<f32> {
+    let rate = csg_pop_f32();
+    push_pos(pos);
+    let scale = max(1.0 + rate * pos.y, taper_min_scale);
+    return vec3<f32>(pos.x / scale, pos.y, pos.z / scale);
+}
+
+fn eval_cmd_elongate_push(pos: vec3<f32>) -> vec3<f32> {
+    let length = csg_pop_vec3();
+    push_pos(pos);
+    return pos - clamp(pos, -length, length);
+}
+
+fn eval_cmd_extrude_push(pos: vec3<f32>) -> vec3<f32> {
+    push_pos(pos);
+    return vec3<f32>(pos.xy, 0.0);
+}
+
+fn eval_cmd_extrude_pop() -> vec3<f32> {
+    // Intersect the infinitely extruded profile with the slab of the given depth.
+    let half_depth = csg_pop_f32();
+    let pos = pop_pos();
+    let w = vec2<f32>(pop_value(), abs(pos.z) - half_depth);
+    push_value(min(max(w.x, w.y), 0.0) + length(max(w, vec2(0.0))));
+    return pos;
+}
+
+fn eval_cmd_revolve_push(pos: vec3<f32>) -> vec3<f32> {
+    let offset = csg_pop_f32();
+    push_pos(pos);
+    return vec3<f32>(length(pos.xz) - offset, pos.y, 0.0);
+}
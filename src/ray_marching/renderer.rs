@@ -1,10 +1,11 @@
 use std::f32::consts::FRAC_PI_4;
+use std::sync::Arc;
 
 use eframe::egui::PaintCallbackInfo;
 use eframe::egui_wgpu::{CallbackResources, CallbackTrait, RenderState};
 use encase::internal::WriteInto;
 use encase::{ShaderType, UniformBuffer};
-use nalgebra::{Matrix4, Perspective3, Vector2};
+use nalgebra::{Matrix4, Perspective3, Vector2, Vector3};
 use wgpu::util::DeviceExt;
 use wgpu::{
     CommandBuffer, CommandEncoder, Device, PrimitiveState, PrimitiveTopology, Queue, RenderPass,
@@ -12,7 +13,8 @@ use wgpu::{
 
 use crate::camera::Camera;
 use crate::ray_marching::csg::builder::CSGCommandBufferBuilder;
-use crate::ray_marching::csg::{BuildCommands, CSGNode};
+use crate::ray_marching::csg::{BuildCommands, CSGNode, TextGrid};
+use crate::ray_marching::sdf_grid::SdfGrid;
 
 trait AsShaderBytes {
     fn as_shader_bytes(&self) -> Box<[u8]>;
@@ -31,6 +33,7 @@ struct Uniforms {
     viewport_extent: Vector2<f32>,
     inv_proj: Matrix4<f32>,
     inv_view: Matrix4<f32>,
+    time: f32,
 }
 
 #[derive(Debug, Copy, Clone, ShaderType)]
@@ -40,12 +43,31 @@ struct RayMarchLimits {
     max_iter: u32,
 }
 
+/// Bounds of the samples in the grid SDF texture.
+#[derive(Debug, Copy, Clone, ShaderType)]
+struct GridSdfBounds {
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+}
+
 pub struct RayMarchingResources {
     pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
     bind_group: wgpu::BindGroup,
+    bindings: Bindings,
+
+    /// Grids stacked into the text atlas texture, to upload them again only when they change.
+    text_atlas_grids: Vec<Arc<TextGrid>>,
+}
 
+/// Buffers and textures bound to the shader, in the order of their bindings.
+struct Bindings {
+    ray_march_limits_buffer: wgpu::Buffer,
     cmd_buffer: wgpu::Buffer,
     uniforms_buffer: wgpu::Buffer,
+    grid_sdf_texture: wgpu::Texture,
+    grid_sdf_bounds_buffer: wgpu::Buffer,
+    text_atlas_texture: wgpu::Texture,
 }
 
 impl RayMarchingResources {
@@ -90,6 +112,36 @@ impl RayMarchingResources {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D3,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -139,38 +191,227 @@ impl RayMarchingResources {
                 usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             });
 
-        let cmd_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("ray_marching_cmd_buffer"),
-            size: 1024,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        let grid_sdf_bounds_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("grid_sdf_bounds"),
+            size: GridSdfBounds::min_size().get(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        let bindings = Bindings {
+            ray_march_limits_buffer,
+            cmd_buffer: create_cmd_buffer(device, 1024),
+            uniforms_buffer,
+            grid_sdf_texture: create_grid_sdf_texture(device, [1, 1, 1]),
+            grid_sdf_bounds_buffer,
+            text_atlas_texture: create_text_atlas_texture(device, [1, 1]),
+        };
+
+        let mut resources = Self {
+            pipeline,
+            bind_group: bindings.create_bind_group(device, &bind_group_layout),
+            bind_group_layout,
+            bindings,
+            text_atlas_grids: Vec::new(),
+        };
+
+        // Until a mesh is baked, the grid SDF is empty.
+        resources.upload_grid_sdf(
+            device,
+            &wgpu_render_state.queue,
+            &SdfGrid {
+                resolution: [1, 1, 1],
+                min: [-1.0; 3],
+                max: [1.0; 3],
+                values: vec![f32::MAX],
+            },
+        );
+        resources
+    }
+
+    /// Recreate the bind group after replacing one of the bound buffers or textures.
+    fn rebind(&mut self, device: &Device) {
+        self.bind_group = self
+            .bindings
+            .create_bind_group(device, &self.bind_group_layout);
+    }
+
+    /// Grow the command buffer to hold at least `size` bytes.
+    fn reserve_cmd_buffer(&mut self, device: &Device, size: u64) {
+        if self.bindings.cmd_buffer.size() >= size {
+            return;
+        }
+
+        self.bindings.cmd_buffer = create_cmd_buffer(device, size.next_power_of_two());
+        self.rebind(device);
+    }
+
+    /// Upload the grid sampled by the `GridSdf` node, replacing the previous one.
+    pub(crate) fn upload_grid_sdf(&mut self, device: &Device, queue: &Queue, grid: &SdfGrid) {
+        let [width, height, depth] = grid.resolution;
+        if self.bindings.grid_sdf_texture.size().width != width
+            || self.bindings.grid_sdf_texture.size().height != height
+            || self.bindings.grid_sdf_texture.size().depth_or_array_layers != depth
+        {
+            self.bindings.grid_sdf_texture = create_grid_sdf_texture(device, grid.resolution);
+            self.rebind(device);
+        }
+
+        queue.write_texture(
+            self.bindings.grid_sdf_texture.as_image_copy(),
+            bytemuck::cast_slice(&grid.values),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            self.bindings.grid_sdf_texture.size(),
+        );
+        queue.write_buffer(
+            &self.bindings.grid_sdf_bounds_buffer,
+            0,
+            &GridSdfBounds {
+                min: grid.min.into(),
+                max: grid.max.into(),
+            }
+            .as_shader_bytes(),
+        );
+    }
+
+    /// Upload the grids of the text nodes, stacked vertically in the order of `grids`, unless
+    /// they are the grids uploaded last.
+    fn upload_text_atlas(&mut self, device: &Device, queue: &Queue, grids: &[Arc<TextGrid>]) {
+        let uploaded = self.text_atlas_grids.len() == grids.len()
+            && (self.text_atlas_grids.iter())
+                .zip(grids)
+                .all(|(a, b)| Arc::ptr_eq(a, b));
+        if uploaded {
+            return;
+        }
+
+        let width = grids.iter().map(|grid| grid.width()).max().unwrap_or(1);
+        // Scenes with more text than fits are reported in the node graph, see
+        // `TEXT_ATLAS_MAX_SIZE`.
+        let height = grids.iter().map(|grid| grid.height()).sum::<u32>().max(1);
+
+        // The atlas only grows, so that it isn't recreated for every edit of a text.
+        let size = self.bindings.text_atlas_texture.size();
+        if size.width < width || size.height < height {
+            self.bindings.text_atlas_texture =
+                create_text_atlas_texture(device, [size.width.max(width), size.height.max(height)]);
+            self.rebind(device);
+        }
+
+        let mut row = 0;
+        for grid in grids {
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &self.bindings.text_atlas_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x: 0, y: row, z: 0 },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                bytemuck::cast_slice(grid.samples()),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * grid.width()),
+                    rows_per_image: Some(grid.height()),
+                },
+                wgpu::Extent3d {
+                    width: grid.width(),
+                    height: grid.height(),
+                    depth_or_array_layers: 1,
+                },
+            );
+            row += grid.height();
+        }
+        self.text_atlas_grids = grids.to_vec();
+    }
+}
+
+fn create_cmd_buffer(device: &Device, size: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("ray_marching_cmd_buffer"),
+        size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_grid_sdf_texture(device: &Device, [width, height, depth]: [u32; 3]) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("grid_sdf"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: depth,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D3,
+        format: wgpu::TextureFormat::R32Float,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+fn create_text_atlas_texture(device: &Device, [width, height]: [u32; 2]) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("text_atlas"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R32Float,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+impl Bindings {
+    fn create_bind_group(
+        &self,
+        device: &Device,
+        layout: &wgpu::BindGroupLayout,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("ray_marching"),
-            layout: &bind_group_layout,
+            layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: ray_march_limits_buffer.as_entire_binding(),
+                    resource: self.ray_march_limits_buffer.as_entire_binding(),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: cmd_buffer.as_entire_binding(),
+                    resource: self.cmd_buffer.as_entire_binding(),
                 },
                 wgpu::BindGroupEntry {
                     binding: 2,
-                    resource: uniforms_buffer.as_entire_binding(),
+                    resource: self.uniforms_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(
+                        &self.grid_sdf_texture.create_view(&Default::default()),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.grid_sdf_bounds_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::TextureView(
+                        &self.text_atlas_texture.create_view(&Default::default()),
+                    ),
                 },
             ],
-        });
-
-        Self {
-            pipeline,
-            bind_group,
-            cmd_buffer,
-            uniforms_buffer,
-        }
+        })
     }
 }
 
@@ -195,12 +436,12 @@ impl RayMarchingCallback {
 impl CallbackTrait for RayMarchingCallback {
     fn prepare(
         &self,
-        _device: &Device,
+        device: &Device,
         queue: &Queue,
         _egui_encoder: &mut CommandEncoder,
         callback_resources: &mut CallbackResources,
     ) -> Vec<CommandBuffer> {
-        let resources: &RayMarchingResources = callback_resources.get().unwrap();
+        let resources: &mut RayMarchingResources = callback_resources.get_mut().unwrap();
 
         // TODO: Make projection configurable
         let projection =
@@ -211,12 +452,13 @@ impl CallbackTrait for RayMarchingCallback {
         let inv_view = self.camera.view().inverse().to_homogeneous();
 
         queue.write_buffer(
-            &resources.uniforms_buffer,
+            &resources.bindings.uniforms_buffer,
             0,
             &Uniforms {
                 viewport_extent,
                 inv_proj,
                 inv_view,
+                time: self.time,
             }
             .as_shader_bytes(),
         );
@@ -225,16 +467,22 @@ impl CallbackTrait for RayMarchingCallback {
         if let Some(csg_node) = &self.csg_node {
             csg_node.build_commands(&mut builder);
         }
+        resources.upload_text_atlas(device, queue, &builder.text_grids);
 
-        // TODO: Recreate the buffers if they are too small
+        resources.reserve_cmd_buffer(device, 8 + 4 * builder.buffer.len() as u64);
         queue.write_buffer(
-            &resources.cmd_buffer,
+            &resources.bindings.cmd_buffer,
             0,
             bytemuck::cast_slice(&[builder.cmd_count]),
         );
         queue.write_buffer(
-            &resources.cmd_buffer,
+            &resources.bindings.cmd_buffer,
             4,
+            bytemuck::cast_slice(&[1.0 / builder.lipschitz]),
+        );
+        queue.write_buffer(
+            &resources.bindings.cmd_buffer,
+            8,
             bytemuck::cast_slice(&builder.buffer),
         );
 
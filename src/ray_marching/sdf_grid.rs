@@ -0,0 +1,212 @@
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use nalgebra::Vector3;
+
+type Triangle = [Vector3<f32>; 3];
+
+/// Signed distances sampled on a regular grid, uploaded as a 3D texture for the `GridSdf` node.
+#[derive(Debug, Clone)]
+pub(crate) struct SdfGrid {
+    /// Number of samples along each axis.
+    pub(crate) resolution: [u32; 3],
+    /// Position of the first sample.
+    pub(crate) min: [f32; 3],
+    /// Position of the last sample.
+    pub(crate) max: [f32; 3],
+    /// Samples in x-major order, i.e. `values[x + resolution[0] * (y + resolution[1] * z)]`.
+    pub(crate) values: Vec<f32>,
+}
+
+#[derive(Debug)]
+pub(crate) enum MeshLoadError {
+    UnsupportedFormat,
+    Io(std::io::Error),
+    Obj(tobj::LoadError),
+    Empty,
+}
+
+impl fmt::Display for MeshLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshLoadError::UnsupportedFormat => {
+                write!(f, "unsupported mesh format, expected OBJ or STL")
+            }
+            MeshLoadError::Io(err) => write!(f, "failed to read mesh: {err}"),
+            MeshLoadError::Obj(err) => write!(f, "failed to parse OBJ: {err}"),
+            MeshLoadError::Empty => write!(f, "mesh has no triangles"),
+        }
+    }
+}
+
+impl SdfGrid {
+    /// Load a triangle mesh from an OBJ or STL file and bake it into a grid with `resolution`
+    /// samples along its longest axis. The mesh should be closed for the sign to be meaningful.
+    pub(crate) fn bake_mesh_file(path: &Path, resolution: u32) -> Result<Self, MeshLoadError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        let triangles = match extension.as_deref() {
+            Some("obj") => load_obj(path)?,
+            Some("stl") => load_stl(path)?,
+            _ => return Err(MeshLoadError::UnsupportedFormat),
+        };
+        if triangles.is_empty() {
+            return Err(MeshLoadError::Empty);
+        }
+        Ok(Self::bake(&triangles, resolution))
+    }
+
+    /// Bake a triangle mesh into a grid with `resolution` samples along its longest axis.
+    fn bake(triangles: &[Triangle], resolution: u32) -> Self {
+        let mut min = triangles[0][0];
+        let mut max = triangles[0][0];
+        for vertex in triangles.iter().flatten() {
+            min = min.inf(vertex);
+            max = max.sup(vertex);
+        }
+
+        // Pad the bounds, so the surface doesn't touch the edges of the grid.
+        let padding = 0.1 * (max - min).max() + f32::EPSILON;
+        min.add_scalar_mut(-padding);
+        max.add_scalar_mut(padding);
+
+        let spacing = (max - min).max() / (resolution.max(2) - 1) as f32;
+        let resolution = (max - min).map(|extent| (extent / spacing).ceil() as u32 + 1);
+        let max = min + resolution.map(|samples| (samples - 1) as f32) * spacing;
+
+        let slice_len = (resolution.x * resolution.y) as usize;
+        let mut values = vec![0.0; slice_len * resolution.z as usize];
+
+        // Each thread bakes a contiguous range of z-slices.
+        let thread_count = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let slices_per_thread = (resolution.z as usize).div_ceil(thread_count);
+        std::thread::scope(|scope| {
+            for (chunk_index, chunk) in values.chunks_mut(slices_per_thread * slice_len).enumerate()
+            {
+                scope.spawn(move || {
+                    for (i, value) in chunk.iter_mut().enumerate() {
+                        let i = chunk_index * slices_per_thread * slice_len + i;
+                        let x = i % resolution.x as usize;
+                        let y = i / resolution.x as usize % resolution.y as usize;
+                        let z = i / slice_len;
+                        let pos = min + Vector3::new(x as f32, y as f32, z as f32) * spacing;
+                        *value = signed_distance(triangles, pos);
+                    }
+                });
+            }
+        });
+
+        Self {
+            resolution: resolution.into(),
+            min: min.into(),
+            max: max.into(),
+            values,
+        }
+    }
+}
+
+fn load_obj(path: &Path) -> Result<Vec<Triangle>, MeshLoadError> {
+    let (models, _) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS).map_err(MeshLoadError::Obj)?;
+    let mut triangles = Vec::new();
+    for model in models {
+        let mesh = model.mesh;
+        let vertex = |index: u32| {
+            let i = 3 * index as usize;
+            Vector3::new(
+                mesh.positions[i],
+                mesh.positions[i + 1],
+                mesh.positions[i + 2],
+            )
+        };
+        for face in mesh.indices.chunks_exact(3) {
+            triangles.push([vertex(face[0]), vertex(face[1]), vertex(face[2])]);
+        }
+    }
+    Ok(triangles)
+}
+
+fn load_stl(path: &Path) -> Result<Vec<Triangle>, MeshLoadError> {
+    let file = File::open(path).map_err(MeshLoadError::Io)?;
+    let mesh = stl_io::read_stl(&mut BufReader::new(file)).map_err(MeshLoadError::Io)?;
+    Ok(mesh
+        .faces
+        .iter()
+        .map(|face| {
+            face.vertices
+                .map(|index| Vector3::from(mesh.vertices[index].0))
+        })
+        .collect())
+}
+
+/// Distance to the closest triangle, negative inside the mesh.
+/// Inside is decided by the generalized winding number, which is robust to small holes.
+fn signed_distance(triangles: &[Triangle], pos: Vector3<f32>) -> f32 {
+    let mut distance_squared = f32::INFINITY;
+    let mut solid_angle = 0.0;
+    for triangle in triangles {
+        let closest = closest_point_on_triangle(triangle, pos);
+        distance_squared = distance_squared.min((closest - pos).norm_squared());
+
+        let [a, b, c] = triangle.map(|vertex| vertex - pos);
+        let (la, lb, lc) = (a.norm(), b.norm(), c.norm());
+        let numerator = a.dot(&b.cross(&c));
+        let denominator = la * lb * lc + a.dot(&b) * lc + b.dot(&c) * la + c.dot(&a) * lb;
+        solid_angle += 2.0 * numerator.atan2(denominator);
+    }
+
+    let winding_number = solid_angle / (4.0 * std::f32::consts::PI);
+    let distance = distance_squared.sqrt();
+    if winding_number.abs() > 0.5 {
+        -distance
+    } else {
+        distance
+    }
+}
+
+/// Closest point on a triangle, see "Real-Time Collision Detection" by Christer Ericson.
+fn closest_point_on_triangle([a, b, c]: &Triangle, p: Vector3<f32>) -> Vector3<f32> {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return *a;
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return *b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return *c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let denominator = 1.0 / (va + vb + vc);
+    a + ab * (vb * denominator) + ac * (vc * denominator)
+}
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::animation::Interpolation;
+use crate::camera::OrbitCameraPose;
+
+/// Version written to new scene files. Bump it when the format changes incompatibly.
+pub(crate) const SCENE_VERSION: u32 = 1;
+
+/// Contents of a scene file, stored as RON.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Scene {
+    pub(crate) version: u32,
+    pub(crate) camera: OrbitCameraPose,
+    pub(crate) graph: SceneGraph,
+    /// Definitions of the groups in the library, so that the scene opens on its own.
+    #[serde(default)]
+    pub(crate) groups: BTreeMap<String, SceneGraph>,
+}
+
+/// Only the version of a scene file, read before the rest so that files from newer versions fail
+/// with a clear error instead of a parse error.
+#[derive(Deserialize)]
+#[serde(rename = "Scene")]
+struct SceneVersion {
+    version: u32,
+}
+
+/// Nodes copied to the clipboard, stored as RON text so that they can be pasted in another session.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Snippet {
+    pub(crate) version: u32,
+    pub(crate) graph: SceneGraph,
+    /// Definitions of the groups used by the nodes, for pasting where the library lacks them.
+    #[serde(default)]
+    pub(crate) groups: BTreeMap<String, SceneGraph>,
+}
+
+/// Only the version of a snippet, see [`SceneVersion`].
+#[derive(Deserialize)]
+#[serde(rename = "Snippet")]
+struct SnippetVersion {
+    version: u32,
+}
+
+/// Node graph in a form independent of the ids of the editor, nodes are referred to by their index
+/// in `nodes`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct SceneGraph {
+    pub(crate) nodes: Vec<SceneNode>,
+    pub(crate) connections: Vec<SceneConnection>,
+    #[serde(default)]
+    pub(crate) tracks: Vec<SceneTrack>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SceneNode {
+    /// Name of the node template, as shown in the node finder.
+    pub(crate) template: String,
+    pub(crate) position: [f32; 2],
+    /// All inputs of the node, so that added variadic inputs are restored.
+    pub(crate) inputs: Vec<SceneInput>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SceneInput {
+    pub(crate) name: String,
+    /// Constant value, `None` for inputs that only take connections.
+    #[serde(default)]
+    pub(crate) value: Option<SceneValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum SceneValue {
+    Scalar(f32),
+    Vec3([f32; 3]),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SceneConnection {
+    /// Index of the node of the output.
+    pub(crate) from: usize,
+    pub(crate) output: String,
+    /// Index of the node of the input.
+    pub(crate) to: usize,
+    pub(crate) input: String,
+}
+
+/// Keyframes of an animated input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SceneTrack {
+    pub(crate) node: usize,
+    pub(crate) input: String,
+    pub(crate) keyframes: Vec<SceneKeyframe>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SceneKeyframe {
+    pub(crate) time: f32,
+    pub(crate) value: SceneValue,
+    pub(crate) interpolation: Interpolation,
+}
+
+#[derive(Debug)]
+pub(crate) enum SceneError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    UnsupportedVersion(u32),
+    UnknownNodeType(String),
+    UnknownInput {
+        node: usize,
+        input: String,
+    },
+    /// The value of an input or keyframe doesn't have the type of the input.
+    ValueTypeMismatch {
+        node: usize,
+        input: String,
+    },
+    BadConnection {
+        connection: usize,
+        reason: String,
+    },
+    BadTrack {
+        track: usize,
+        reason: String,
+    },
+    BadGroup {
+        group: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "{err}"),
+            SceneError::Parse(err) => write!(f, "invalid scene file: {err}"),
+            SceneError::Serialize(err) => write!(f, "failed to write scene: {err}"),
+            SceneError::UnsupportedVersion(version) => write!(
+                f,
+                "scene file version {version} is not supported, expected at most {SCENE_VERSION}"
+            ),
+            SceneError::UnknownNodeType(template) => write!(f, "unknown node type {template:?}"),
+            SceneError::UnknownInput { node, input } => {
+                write!(f, "node {node} has no input {input:?}")
+            }
+            SceneError::ValueTypeMismatch { node, input } => {
+                write!(f, "wrong value type for input {input:?} of node {node}")
+            }
+            SceneError::BadConnection { connection, reason } => {
+                write!(f, "bad connection {connection}: {reason}")
+            }
+            SceneError::BadTrack { track, reason } => write!(f, "bad track {track}: {reason}"),
+            SceneError::BadGroup { group, reason } => write!(f, "bad group {group:?}: {reason}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SceneError {
+    fn from(err: std::io::Error) -> Self {
+        SceneError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for SceneError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        SceneError::Parse(err)
+    }
+}
+
+impl From<ron::Error> for SceneError {
+    fn from(err: ron::Error) -> Self {
+        SceneError::Serialize(err)
+    }
+}
+
+impl Scene {
+    pub(crate) fn new(
+        camera: OrbitCameraPose,
+        graph: SceneGraph,
+        groups: BTreeMap<String, SceneGraph>,
+    ) -> Self {
+        Self {
+            version: SCENE_VERSION,
+            camera,
+            graph,
+            groups,
+        }
+    }
+
+    pub(crate) fn load(path: &Path) -> Result<Self, SceneError> {
+        let text = std::fs::read_to_string(path)?;
+        let SceneVersion { version } = ron::from_str(&text)?;
+        if version > SCENE_VERSION {
+            return Err(SceneError::UnsupportedVersion(version));
+        }
+        Ok(ron::from_str(&text)?)
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<(), SceneError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+impl Snippet {
+    pub(crate) fn new(graph: SceneGraph, groups: BTreeMap<String, SceneGraph>) -> Self {
+        Self {
+            version: SCENE_VERSION,
+            graph,
+            groups,
+        }
+    }
+
+    pub(crate) fn from_text(text: &str) -> Result<Self, SceneError> {
+        let SnippetVersion { version } = ron::from_str(text)?;
+        if version > SCENE_VERSION {
+            return Err(SceneError::UnsupportedVersion(version));
+        }
+        Ok(ron::from_str(text)?)
+    }
+
+    /// Single line of text, to keep pasting it into chats tidy.
+    pub(crate) fn to_text(&self) -> Result<String, SceneError> {
+        Ok(ron::to_string(self)?)
+    }
+}